@@ -1,11 +1,36 @@
-use crate::sum::Sum;
-use std::{
-    collections::BTreeMap,
-    fmt,
-    ops::{Add, AddAssign, Sub, SubAssign},
+use crate::{
+    book::AccountKey,
+    exchange_rates::ExchangeRates,
+    format::{self, Locale},
+    move_::Move,
+    rounding_mode::RoundingMode,
+    sum::Sum,
 };
+use alloc::{
+    collections::{btree_map, BTreeMap},
+    string::{String, ToString},
+};
+use core::{
+    cmp::Ordering,
+    convert::{TryFrom, TryInto},
+    error, fmt,
+    ops::{Add, AddAssign, Div, Neg, Rem, Sub, SubAssign},
+};
+use num_traits::{CheckedAdd, CheckedMul, CheckedNeg};
 /// Represents a [balance](https://en.wikipedia.org/wiki/Balance_(accounting)), yet not necessarily the current balance.
+///
+/// With the `serde` feature, a balance serializes as a map from units to
+/// amounts.
 #[derive(PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        transparent,
+        bound(deserialize = "Unit: Ord + serde::Deserialize<'de>, \
+                             Number: serde::Deserialize<'de>")
+    )
+)]
 pub struct Balance<Unit, Number>(pub(crate) BTreeMap<Unit, Number>);
 impl<Unit, Number> Balance<Unit, Number>
 where
@@ -41,14 +66,276 @@ where
             })
             .or_insert_with(|| amount_op(Default::default(), amount.clone()));
     }
+    /// Calculates the balance of an account over some moves.
+    ///
+    /// Moves out of the account are subtracted and moves into it are added.
+    /// Moves that do not involve the account are skipped.
+    pub fn from_moves<'a, SumNumber, MoveExtra>(
+        moves: impl IntoIterator<Item = &'a Move<Unit, SumNumber, MoveExtra>>,
+        account_key: AccountKey,
+    ) -> Self
+    where
+        Unit: 'a,
+        Number: Default + Sub<Output = Number> + Add<Output = Number> + Clone,
+        SumNumber: Clone + Into<Number> + 'a,
+        MoveExtra: 'a,
+    {
+        moves
+            .into_iter()
+            .fold(Self::default(), |mut balance, move_| {
+                if move_.debit_account_key == account_key {
+                    balance -= &move_.sum;
+                } else if move_.credit_account_key == account_key {
+                    balance += &move_.sum;
+                }
+                balance
+            })
+    }
     /// Gets the amounts of all units in undefined order.
     pub fn amounts(&self) -> impl Iterator<Item = (&Unit, &Number)> {
         self.0.iter()
     }
+    /// Splits the balance into the units with positive amounts and the units
+    /// with negative amounts.
+    ///
+    /// Negative amounts are kept signed. Units with an amount of zero, taken
+    /// to be the [Default] of `Number`, are in neither.
+    pub fn split_positive_negative(&self) -> (Self, Self)
+    where
+        Number: Default + PartialOrd + Clone,
+    {
+        let zero = Number::default();
+        self.0.iter().fold(
+            (Self::default(), Self::default()),
+            |(mut positive, mut negative), (unit, amount)| {
+                if *amount > zero {
+                    positive.0.insert(unit.clone(), amount.clone());
+                } else if *amount < zero {
+                    negative.0.insert(unit.clone(), amount.clone());
+                }
+                (positive, negative)
+            },
+        )
+    }
+    /// Gets the balance with the absolute value of each amount.
+    ///
+    /// Zero is taken to be the [Default] of `Number`.
+    ///
+    /// ## Panics
+    ///
+    /// - Some amount has no absolute value in `Number`, such as `i128::MIN`.
+    pub fn abs(&self) -> Self
+    where
+        Number: Default + PartialOrd + Clone + CheckedNeg,
+    {
+        let zero = Number::default();
+        Self(
+            self.0
+                .iter()
+                .map(|(unit, amount)| {
+                    let amount = if *amount < zero {
+                        amount
+                            .checked_neg()
+                            .expect("Absolute value of amount overflowed.")
+                    } else {
+                        amount.clone()
+                    };
+                    (unit.clone(), amount)
+                })
+                .collect(),
+        )
+    }
+    /// Rounds the amount of each unit to the precision of that unit.
+    ///
+    /// Amounts are taken to have `decimal_places` decimal places.
+    /// `precision_of` provides the decimal places of a unit, which the amounts
+    /// of that unit in the result have. So, an amount of `12345` with `3`
+    /// decimal places, rounded to a precision of `2`, is `1235` with
+    /// [RoundingMode::HalfUp].
+    ///
+    /// ## Panics
+    ///
+    /// - Ten to the power of the difference between `decimal_places` and the
+    ///   precision of some unit overflows `Number`, such as `10^39` for
+    ///   `i128`.
+    /// - Some amount overflows `Number` when increased to the precision of
+    ///   its unit.
+    pub fn round(
+        &self,
+        decimal_places: u8,
+        precision_of: impl Fn(&Unit) -> u8,
+        mode: RoundingMode,
+    ) -> Self
+    where
+        Number: Clone
+            + Default
+            + PartialOrd
+            + From<u8>
+            + Add<Output = Number>
+            + Sub<Output = Number>
+            + CheckedMul
+            + Div<Output = Number>
+            + Rem<Output = Number>,
+    {
+        Self(
+            self.0
+                .iter()
+                .map(|(unit, amount)| {
+                    let precision = precision_of(unit);
+                    let amount = if precision < decimal_places {
+                        mode.divide(
+                            amount.clone(),
+                            format::power_of_ten(decimal_places - precision),
+                        )
+                    } else {
+                        amount
+                            .checked_mul(&format::power_of_ten(
+                                precision - decimal_places,
+                            ))
+                            .expect("Rounded amount overflowed.")
+                    };
+                    (unit.clone(), amount)
+                })
+                .collect(),
+        )
+    }
     /// Gets the amount of a provided unit.
     pub fn unit_amount(&self, unit: &Unit) -> Option<&Number> {
         self.0.get(unit)
     }
+    /// Formats the amount of a unit with a decimal point, right-aligned to a
+    /// column of `width` characters, such as `   -12.34`.
+    ///
+    /// See [Sum::format_aligned].
+    pub fn format_aligned(
+        &self,
+        unit: &Unit,
+        width: usize,
+        decimal_places: u8,
+    ) -> String
+    where
+        Number: Default + fmt::Display,
+    {
+        format::aligned(self.0.get(unit), width, decimal_places)
+    }
+    /// Values the balance in a single unit.
+    ///
+    /// Amounts of the `target` unit are taken as they are.
+    /// Amounts of any other unit are multiplied by the rate from that unit to
+    /// the `target` unit, each rounded to a whole number by `mode`.
+    ///
+    /// Returns `None` if a rate from some unit in the balance to the `target`
+    /// unit is missing, or if the value overflows.
+    ///
+    /// ## Panics
+    ///
+    /// - The denominator of some rate that is used is not positive.
+    pub fn value_in(
+        &self,
+        target: &Unit,
+        rates: &ExchangeRates<Unit, Number>,
+        mode: RoundingMode,
+    ) -> Option<Number>
+    where
+        Number: Clone
+            + Default
+            + PartialOrd
+            + From<u8>
+            + Add<Output = Number>
+            + Sub<Output = Number>
+            + CheckedAdd
+            + CheckedMul
+            + Div<Output = Number>
+            + Rem<Output = Number>,
+    {
+        self.0
+            .iter()
+            .try_fold(Number::default(), |value, (unit, amount)| {
+                let amount = if unit == target {
+                    amount.clone()
+                } else {
+                    rates.rate(unit, target)?.apply(amount, mode)?
+                };
+                value.checked_add(&amount)
+            })
+    }
+}
+impl<Unit, Number> Balance<Unit, Number>
+where
+    Unit: Ord,
+{
+    /// Gets a value that displays the balance using symbols and decimal
+    /// places provided for each unit, such as `USD -12.34; EUR 5.00`.
+    ///
+    /// `unit_format` provides the symbol and the number of decimal places of a
+    /// unit. Amounts are taken to be in minor units, so an amount of `1234`
+    /// with `2` decimal places is displayed as `12.34`.
+    pub fn display_with<'a, Symbol>(
+        &'a self,
+        unit_format: impl Fn(&Unit) -> (Symbol, u8) + 'a,
+    ) -> impl fmt::Display + 'a
+    where
+        Symbol: fmt::Display,
+        Number: fmt::Display,
+    {
+        DisplayWith {
+            balance: self,
+            unit_format,
+            locale: None,
+        }
+    }
+    /// Formats the balance like [Balance::display_with], by the number
+    /// conventions of a locale, such as `USD -1,234.56; EUR 5,00`.
+    pub fn format_localized<Symbol>(
+        &self,
+        unit_format: impl Fn(&Unit) -> (Symbol, u8),
+        locale: &Locale,
+    ) -> String
+    where
+        Symbol: fmt::Display,
+        Number: fmt::Display,
+    {
+        DisplayWith {
+            balance: self,
+            unit_format,
+            locale: Some(locale),
+        }
+        .to_string()
+    }
+}
+struct DisplayWith<'a, Unit, Number, UnitFormat>
+where
+    Unit: Ord,
+{
+    balance: &'a Balance<Unit, Number>,
+    unit_format: UnitFormat,
+    locale: Option<&'a Locale>,
+}
+impl<'a, Unit, Number, UnitFormat, Symbol> fmt::Display
+    for DisplayWith<'a, Unit, Number, UnitFormat>
+where
+    Unit: Ord,
+    Number: fmt::Display,
+    UnitFormat: Fn(&Unit) -> (Symbol, u8),
+    Symbol: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.balance.0.iter().enumerate().try_for_each(
+            |(index, (unit, amount))| {
+                if index > 0 {
+                    f.write_str("; ")?;
+                }
+                let (symbol, decimal_places) = (self.unit_format)(unit);
+                let amount = match self.locale {
+                    Some(locale) => {
+                        format::localized(amount, decimal_places, locale)
+                    }
+                    None => format::decimal(amount, decimal_places),
+                };
+                write!(f, "{} {}", symbol, amount)
+            },
+        )
+    }
 }
 impl<Unit, Number> Default for Balance<Unit, Number>
 where
@@ -58,6 +345,46 @@ where
         Self(Default::default())
     }
 }
+/// Balances are ordered partially: `a <= b` when the amount of every unit in
+/// `a` is at most its amount in `b`, a missing amount counting as zero.
+///
+/// Balances with one amount lower and another higher are incomparable. So are
+/// balances that differ only in amounts of zero being present or missing, to
+/// stay consistent with `==`.
+impl<Unit, Number> PartialOrd for Balance<Unit, Number>
+where
+    Unit: Ord,
+    Number: Default + PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let zero = Number::default();
+        let (less, greater) = self.0.keys().chain(other.0.keys()).try_fold(
+            (false, false),
+            |(less, greater), unit| {
+                let amount = self.0.get(unit).unwrap_or(&zero);
+                let other_amount = other.0.get(unit).unwrap_or(&zero);
+                match amount.partial_cmp(other_amount)? {
+                    Ordering::Less => Some((true, greater)),
+                    Ordering::Equal => Some((less, greater)),
+                    Ordering::Greater => Some((less, true)),
+                }
+            },
+        )?;
+        match (less, greater) {
+            (false, false) if self == other => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            _ => None,
+        }
+    }
+}
+impl<'a, Unit, Number> IntoIterator for &'a Balance<Unit, Number> {
+    type Item = (&'a Unit, &'a Number);
+    type IntoIter = btree_map::Iter<'a, Unit, Number>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
 impl<Unit, Number> fmt::Debug for Balance<Unit, Number>
 where
     Unit: fmt::Debug,
@@ -69,6 +396,62 @@ where
         f.write_str(")")
     }
 }
+impl<Unit, Number, SumNumber> From<&Sum<Unit, SumNumber>>
+    for Balance<Unit, Number>
+where
+    Unit: Ord + Clone,
+    Number: Default + Add<Output = Number> + Clone,
+    SumNumber: Clone + Into<Number>,
+{
+    fn from(sum: &Sum<Unit, SumNumber>) -> Self {
+        Self::default() + sum
+    }
+}
+/// The error returned when converting a [Balance] into a [Sum] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TryFromBalanceError<Unit> {
+    /// The unit whose amount is out of the range of the sum number type, for
+    /// example because it is negative.
+    pub unit: Unit,
+}
+impl<Unit> fmt::Display for TryFromBalanceError<Unit>
+where
+    Unit: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "amount of unit {:?} is out of range for a sum",
+            self.unit
+        )
+    }
+}
+impl<Unit> error::Error for TryFromBalanceError<Unit> where Unit: fmt::Debug {}
+/// Succeeds when every amount fits in the sum number type, which excludes
+/// negative amounts for unsigned number types.
+impl<Unit, Number, SumNumber> TryFrom<&Balance<Unit, Number>>
+    for Sum<Unit, SumNumber>
+where
+    Unit: Ord + Clone,
+    Number: Clone + TryInto<SumNumber>,
+{
+    type Error = TryFromBalanceError<Unit>;
+    fn try_from(balance: &Balance<Unit, Number>) -> Result<Self, Self::Error> {
+        balance
+            .0
+            .iter()
+            .map(|(unit, amount)| {
+                amount
+                    .clone()
+                    .try_into()
+                    .map(|amount| (unit.clone(), amount))
+                    .map_err(|_| TryFromBalanceError { unit: unit.clone() })
+            })
+            .collect::<Result<_, _>>()
+            .map(Sum)
+    }
+}
 impl<Unit, Number, SumNumber> SubAssign<&Sum<Unit, SumNumber>>
     for Balance<Unit, Number>
 where
@@ -171,11 +554,44 @@ where
         self
     }
 }
+impl<Unit, Number> Neg for Balance<Unit, Number>
+where
+    Unit: Ord,
+    Number: Neg<Output = Number>,
+{
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self(
+            self.0
+                .into_iter()
+                .map(|(unit, amount)| (unit, -amount))
+                .collect(),
+        )
+    }
+}
+impl<Unit, Number> Neg for &Balance<Unit, Number>
+where
+    Unit: Ord + Clone,
+    Number: Neg<Output = Number> + Clone,
+{
+    type Output = Balance<Unit, Number>;
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
 #[cfg(test)]
 mod test {
-    use super::Balance;
-    use crate::test_utils::TestBalance;
+    use super::{Balance, Locale, RoundingMode, TryFromBalanceError};
+    use crate::{
+        book::TransactionIndex,
+        exchange_rates::{ExchangeRates, Ratio},
+        sum::Sum,
+        test_utils::{TestBalance, TestBook},
+        transaction::MoveIndex,
+    };
+    use core::cmp::Ordering;
     use maplit::btreemap;
+    use std::convert::TryFrom;
     #[test]
     fn default() {
         let actual = TestBalance::default();
@@ -219,6 +635,94 @@ mod test {
         assert_eq!(actual, expected);
     }
     #[test]
+    fn display_with() {
+        let usd = "USD";
+        let jpy = "JPY";
+        let balance = TestBalance::default() - &sum!(1234, usd; 500, jpy);
+        let actual = balance
+            .display_with(|unit| match *unit {
+                "JPY" => ("¥", 0),
+                _ => ("$", 2),
+            })
+            .to_string();
+        assert_eq!(actual, "¥ -500; $ -12.34");
+    }
+    #[test]
+    fn partial_cmp() {
+        let usd = "USD";
+        let thb = "THB";
+        let a = TestBalance::default() + &sum!(1, usd);
+        let b = TestBalance::default() + &sum!(2, usd; 3, thb);
+        assert!(a < b);
+        assert!(b > a);
+        assert!(a <= a.clone());
+        assert_eq!(a.partial_cmp(&a.clone()), Some(Ordering::Equal));
+        let c = TestBalance::default() + &sum!(2, usd) - &sum!(1, thb);
+        assert_eq!(a.partial_cmp(&c), None);
+        assert_eq!(b.partial_cmp(&c), Some(Ordering::Greater));
+        let d = TestBalance::default() + &sum!(3, usd) - &sum!(1, thb);
+        assert_eq!(b.partial_cmp(&d), None);
+        let zero = TestBalance::default() + &sum!(0, usd);
+        assert_eq!(TestBalance::default().partial_cmp(&zero), None);
+    }
+    #[test]
+    fn format_localized() {
+        let usd = "USD";
+        let jpy = "JPY";
+        let balance = TestBalance::default() - &sum!(123456, usd; 1234567, jpy)
+            + &sum!(1234567, usd; 1234567, jpy);
+        let unit_format = |unit: &&str| match *unit {
+            "JPY" => ("¥", 0),
+            _ => ("$", 2),
+        };
+        assert_eq!(
+            balance.format_localized(unit_format, &Locale::EN_US),
+            "¥ 0; $ 11,111.11",
+        );
+        let balance = TestBalance::default() - &sum!(123456, usd; 1000, jpy);
+        assert_eq!(
+            balance.format_localized(unit_format, &Locale::EN_US),
+            "¥ -1,000; $ -1,234.56",
+        );
+        assert_eq!(
+            balance.format_localized(unit_format, &Locale::DE_DE),
+            "¥ -1.000; $ -1.234,56",
+        );
+    }
+    #[test]
+    fn from_sum() {
+        let usd = "USD";
+        let actual = TestBalance::from(&sum!(9, usd));
+        let expected = Balance(btreemap! { usd => 9 });
+        assert_eq!(actual, expected);
+    }
+    #[test]
+    fn try_into_sum() {
+        let usd = "USD";
+        let thb = "THB";
+        let balance = Balance(btreemap! { usd => 9, thb => 0 });
+        let actual = Sum::<&str, u64>::try_from(&balance);
+        assert_eq!(actual, Ok(sum!(9, usd; 0, thb)));
+    }
+    #[test]
+    fn try_into_sum_negative() {
+        let usd = "USD";
+        let thb = "THB";
+        let balance = Balance(btreemap! { usd => 9, thb => -1 });
+        match Sum::<&str, u64>::try_from(&balance) {
+            Err(TryFromBalanceError { unit }) => assert_eq!(unit, thb),
+            actual => panic!("unexpected {:?}", actual),
+        }
+    }
+    #[test]
+    fn try_from_balance_error_fmt_display() {
+        let error = TryFromBalanceError { unit: "THB" };
+        assert_eq!(
+            error.to_string(),
+            "amount of unit \"THB\" is out of range for a sum",
+        );
+    }
+    #[test]
     fn sub_assign_sum() {
         let usd = "USD";
         let mut actual: TestBalance = Default::default();
@@ -259,6 +763,47 @@ mod test {
         assert_eq!(actual, expected);
     }
     #[test]
+    fn from_moves() {
+        let mut book = TestBook::default();
+        let a = book.insert_account("");
+        let b = book.insert_account("");
+        let c = book.insert_account("");
+        let usd = "USD";
+        let thb = "THB";
+        book.insert_transaction(TransactionIndex(0), "");
+        [
+            (a, b, sum!(1, usd), "x"),
+            (b, a, sum!(2, usd; 3, thb), ""),
+            (c, a, sum!(4, thb), "x"),
+            (b, c, sum!(8, usd), "x"),
+        ]
+        .iter()
+        .for_each(|(debit_key, credit_key, sum, extra)| {
+            book.insert_move(
+                TransactionIndex(0),
+                MoveIndex(0),
+                *debit_key,
+                *credit_key,
+                sum.clone(),
+                extra,
+            );
+        });
+        let moves = book
+            .query()
+            .moves()
+            .map(|(_, _, move_)| move_)
+            .filter(|move_| *move_.extra() == "x")
+            .collect::<Vec<_>>();
+        assert_eq!(
+            TestBalance::from_moves(moves.iter().copied(), a),
+            TestBalance::default() - &sum!(1, usd) + &sum!(4, thb),
+        );
+        assert_eq!(
+            TestBalance::from_moves(moves, b),
+            TestBalance::default() + &sum!(1, usd) - &sum!(8, usd),
+        );
+    }
+    #[test]
     fn amounts() {
         let usd = "USD";
         let thb = "THB";
@@ -272,6 +817,29 @@ mod test {
         assert_eq!(actual, expected);
     }
     #[test]
+    fn into_iter() {
+        let usd = "USD";
+        let thb = "THB";
+        let balance = TestBalance::default() + &sum!(100, usd; 200, thb);
+        let mut actual = Vec::new();
+        for (unit, amount) in &balance {
+            actual.push((*unit, *amount));
+        }
+        assert_eq!(actual, vec![(thb, 200), (usd, 100)]);
+    }
+    #[test]
+    fn format_aligned() {
+        let usd = "USD";
+        let thb = "THB";
+        let balance =
+            TestBalance::default() - &sum!(1234, usd) + &sum!(98765, thb);
+        let column = [&usd, &thb, &"ILS"]
+            .iter()
+            .map(|unit| balance.format_aligned(unit, 8, 2))
+            .collect::<Vec<_>>();
+        assert_eq!(column, vec!["  -12.34", "  987.65", "    0.00"]);
+    }
+    #[test]
     fn unit_amount() {
         let usd = "USD";
         let thb = "THB";
@@ -281,4 +849,182 @@ mod test {
         assert_eq!(balance.unit_amount(&thb).unwrap(), &100);
         assert_eq!(balance.unit_amount(&ils), None);
     }
+    #[test]
+    fn value_in() {
+        let usd = "USD";
+        let thb = "THB";
+        let balance = TestBalance::default() + &sum!(2, usd; 100, thb);
+        let mut rates = ExchangeRates::default();
+        rates.set_rate(
+            usd,
+            thb,
+            Ratio {
+                numerator: 30,
+                denominator: 1,
+            },
+        );
+        rates.set_rate(
+            thb,
+            usd,
+            Ratio {
+                numerator: 1,
+                denominator: 30,
+            },
+        );
+        assert_eq!(
+            balance.value_in(&thb, &rates, RoundingMode::HalfUp),
+            Some(160),
+        );
+        assert_eq!(
+            balance.value_in(&usd, &rates, RoundingMode::HalfUp),
+            Some(5),
+        );
+        assert_eq!(
+            balance.value_in(&usd, &rates, RoundingMode::TowardZero),
+            Some(5),
+        );
+        let balance = TestBalance::default() - &sum!(2, usd; 100, thb);
+        assert_eq!(
+            balance.value_in(&usd, &rates, RoundingMode::HalfUp),
+            Some(-5),
+        );
+    }
+    #[test]
+    fn value_in_missing_rate_or_overflow() {
+        let usd = "USD";
+        let thb = "THB";
+        let ils = "ILS";
+        let balance = TestBalance::default() + &sum!(2, usd; 100, thb);
+        let mut rates = ExchangeRates::default();
+        rates.set_rate(
+            usd,
+            thb,
+            Ratio {
+                numerator: i128::MAX,
+                denominator: 1,
+            },
+        );
+        assert_eq!(balance.value_in(&ils, &rates, RoundingMode::HalfUp), None);
+        assert_eq!(balance.value_in(&thb, &rates, RoundingMode::HalfUp), None);
+    }
+    #[test]
+    fn abs() {
+        let usd = "USD";
+        let thb = "THB";
+        let eur = "EUR";
+        let balance =
+            TestBalance::default() + &sum!(5, usd; 0, eur) - &sum!(3, thb);
+        assert_eq!(
+            balance.abs(),
+            TestBalance::default() + &sum!(5, usd; 3, thb; 0, eur),
+        );
+    }
+    #[test]
+    #[should_panic(expected = "Absolute value of amount overflowed.")]
+    fn abs_overflow() {
+        let usd = "USD";
+        Balance(btreemap! { usd => i128::MIN }).abs();
+    }
+    #[test]
+    fn split_positive_negative() {
+        let a = "A";
+        let b = "B";
+        let c = "C";
+        let d = "D";
+        let balance =
+            TestBalance::default() + &sum!(5, a; 0, c; 2, d) - &sum!(3, b);
+        assert_eq!(
+            balance.split_positive_negative(),
+            (
+                TestBalance::default() + &sum!(5, a; 2, d),
+                TestBalance::default() - &sum!(3, b),
+            ),
+        );
+    }
+    #[test]
+    fn round() {
+        let usd = "USD";
+        let jpy = "JPY";
+        let btc = "BTC";
+        let balance = TestBalance::default() + &sum!(12345, usd; 125, jpy)
+            - &sum!(12355, btc);
+        let precision_of = |unit: &&str| match *unit {
+            "USD" => 2,
+            "JPY" => 0,
+            _ => 4,
+        };
+        assert_eq!(
+            balance.round(3, precision_of, RoundingMode::HalfUp),
+            TestBalance::default() + &sum!(1235, usd; 0, jpy)
+                - &sum!(123550, btc),
+        );
+        assert_eq!(
+            balance.round(2, precision_of, RoundingMode::HalfUp),
+            TestBalance::default() + &sum!(12345, usd; 1, jpy)
+                - &sum!(1235500, btc),
+        );
+        assert_eq!(
+            balance.round(3, precision_of, RoundingMode::HalfEven),
+            TestBalance::default() + &sum!(1234, usd; 0, jpy)
+                - &sum!(123550, btc),
+        );
+        assert_eq!(
+            balance.round(1, precision_of, RoundingMode::HalfEven),
+            TestBalance::default() + &sum!(123450, usd; 12, jpy)
+                - &sum!(12355000, btc),
+        );
+        assert_eq!(
+            balance.round(3, precision_of, RoundingMode::TowardZero),
+            TestBalance::default() + &sum!(1234, usd; 0, jpy)
+                - &sum!(123550, btc),
+        );
+    }
+    #[test]
+    fn round_near_max() {
+        let usd = "USD";
+        let balance = TestBalance::default() + &(usd, 9 * 10_i128.pow(37));
+        assert_eq!(
+            balance.round(38, |_| 0, RoundingMode::HalfUp),
+            TestBalance::default() + &(usd, 1),
+        );
+    }
+    #[test]
+    #[should_panic(expected = "Power of ten overflowed.")]
+    fn round_power_of_ten_overflow() {
+        let usd = "USD";
+        let balance = TestBalance::default() + &sum!(1, usd);
+        balance.round(40, |_| 0, RoundingMode::HalfUp);
+    }
+    #[test]
+    #[should_panic(expected = "Rounded amount overflowed.")]
+    fn round_amount_overflow() {
+        let usd = "USD";
+        let balance = TestBalance::default() + &sum!(10, usd);
+        balance.round(0, |_| 38, RoundingMode::HalfUp);
+    }
+    #[test]
+    fn neg() {
+        let a = "A";
+        let b = "B";
+        let balance = TestBalance::default() + &sum!(5, a) - &sum!(3, b);
+        let expected = TestBalance::default() - &sum!(5, a) + &sum!(3, b);
+        assert_eq!(-&balance, expected);
+        assert_eq!(-balance, expected);
+    }
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let usd = "USD";
+        let thb = "THB";
+        let balance = TestBalance::default() - &sum!(1, usd) + &sum!(2, thb);
+        let json = serde_json::to_string(&balance).unwrap();
+        assert_eq!(json, r#"{"THB":2,"USD":-1}"#);
+        let actual: Balance<String, i128> =
+            serde_json::from_str(&json).unwrap();
+        let expected = btreemap! {
+            "THB".to_owned() => 2,
+            "USD".to_owned() => -1,
+        };
+        assert_eq!(actual.0, expected);
+    }
 }
@@ -0,0 +1,226 @@
+use crate::balance::Balance;
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    fmt,
+    ops::{Add, Neg},
+};
+/// Represents the class of an account in a balance sheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AccountClass {
+    #[allow(missing_docs)]
+    Asset,
+    #[allow(missing_docs)]
+    Liability,
+    #[allow(missing_docs)]
+    Equity,
+}
+impl AccountClass {
+    const ALL: [Self; 3] = [Self::Asset, Self::Liability, Self::Equity];
+    fn title(self) -> &'static str {
+        match self {
+            Self::Asset => "Assets",
+            Self::Liability => "Liabilities",
+            Self::Equity => "Equity",
+        }
+    }
+}
+/// Writes a balance sheet of classified account balances.
+///
+/// The balances of liability and equity accounts are negated, so that
+/// amounts owed and invested display as positive.
+pub(crate) fn write<Unit, Number>(
+    w: &mut impl fmt::Write,
+    accounts: Vec<(AccountClass, String, Balance<Unit, Number>)>,
+) -> fmt::Result
+where
+    Unit: Ord + Clone + fmt::Display,
+    Number: Default
+        + Clone
+        + PartialEq
+        + Add<Output = Number>
+        + Neg<Output = Number>
+        + fmt::Display,
+{
+    let units = accounts
+        .iter()
+        .flat_map(|(_, _, balance)| balance.0.keys())
+        .collect::<BTreeSet<_>>();
+    let cells = |balance: &Balance<Unit, Number>| {
+        units
+            .iter()
+            .map(|unit| {
+                balance
+                    .0
+                    .get(*unit)
+                    .cloned()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut rows = vec![(
+        "Account".to_string(),
+        units.iter().map(ToString::to_string).collect::<Vec<_>>(),
+    )];
+    let totals = AccountClass::ALL
+        .iter()
+        .map(|class| {
+            rows.push((class.title().to_string(), Vec::new()));
+            let total = accounts
+                .iter()
+                .filter(|(account_class, _, _)| account_class == class)
+                .fold(Balance::default(), |total, (_, name, balance)| {
+                    let balance = match class {
+                        AccountClass::Asset => balance.clone(),
+                        _ => -balance,
+                    };
+                    rows.push((format!("  {}", name), cells(&balance)));
+                    add(total, &balance)
+                });
+            rows.push((format!("Total {}", class.title()), cells(&total)));
+            total
+        })
+        .collect::<Vec<_>>();
+    let width = |column: usize| {
+        rows.iter()
+            .map(|(name, amounts)| match column {
+                0 => name.len(),
+                _ => amounts.get(column - 1).map_or(0, String::len),
+            })
+            .max()
+            .unwrap_or(0)
+    };
+    let widths = (0..=units.len()).map(width).collect::<Vec<_>>();
+    rows.iter().try_for_each(|(name, amounts)| {
+        let mut line = format!("{:<width$}", name, width = widths[0]);
+        amounts
+            .iter()
+            .zip(&widths[1..])
+            .for_each(|(amount, width)| {
+                line += &format!("  {:>width$}", amount, width = *width);
+            });
+        writeln!(w, "{}", line.trim_end())
+    })?;
+    let liabilities_and_equity = add(totals[1].clone(), &totals[2]);
+    let unbalanced = units
+        .iter()
+        .filter(|unit| {
+            let amount = |balance: &Balance<Unit, Number>| {
+                balance.0.get(**unit).cloned().unwrap_or_default()
+            };
+            amount(&totals[0]) != amount(&liabilities_and_equity)
+        })
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    if unbalanced.is_empty() {
+        writeln!(w, "Assets = Liabilities + Equity")
+    } else {
+        writeln!(
+            w,
+            "Warning: Assets != Liabilities + Equity in {}",
+            unbalanced.join(", ")
+        )
+    }
+}
+fn add<Unit, Number>(
+    mut total: Balance<Unit, Number>,
+    balance: &Balance<Unit, Number>,
+) -> Balance<Unit, Number>
+where
+    Unit: Ord + Clone,
+    Number: Default + Clone + Add<Output = Number>,
+{
+    balance.0.iter().for_each(|(unit, amount)| {
+        total += &(unit.clone(), amount.clone());
+    });
+    total
+}
+#[cfg(test)]
+mod test {
+    use super::AccountClass;
+    use crate::{
+        book::{AccountKey, TransactionIndex},
+        test_utils::TestBook,
+        transaction::MoveIndex,
+    };
+    fn book() -> TestBook {
+        let mut book = TestBook::default();
+        let cash = book.insert_account("cash");
+        let loan = book.insert_account("loan");
+        let capital = book.insert_account("capital");
+        book.insert_account("food");
+        let usd = "USD";
+        let thb = "THB";
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            capital,
+            cash,
+            sum!(100, usd; 5, thb),
+            "",
+        );
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(1),
+            loan,
+            cash,
+            sum!(1500, usd),
+            "",
+        );
+        book
+    }
+    fn classify(_: AccountKey, extra: &&str) -> Option<AccountClass> {
+        match *extra {
+            "cash" => Some(AccountClass::Asset),
+            "loan" => Some(AccountClass::Liability),
+            "capital" => Some(AccountClass::Equity),
+            _ => None,
+        }
+    }
+    #[test]
+    fn balance_sheet() {
+        let book = book();
+        let mut actual = String::new();
+        book.balance_sheet::<i128>(&mut actual, classify).unwrap();
+        let expected = "\
+Account            THB   USD
+Assets
+  cash               5  1600
+Total Assets         5  1600
+Liabilities
+  loan               0  1500
+Total Liabilities    0  1500
+Equity
+  capital            5   100
+Total Equity         5   100
+Assets = Liabilities + Equity
+";
+        assert_eq!(actual, expected);
+    }
+    #[test]
+    fn balance_sheet_unbalanced() {
+        let mut book = book();
+        let usd = "USD";
+        let cash = book.find_account(|extra| *extra == "cash").unwrap().0;
+        let food = book.find_account(|extra| *extra == "food").unwrap().0;
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(2),
+            cash,
+            food,
+            sum!(10, usd),
+            "",
+        );
+        let mut actual = String::new();
+        book.balance_sheet::<i128>(&mut actual, classify).unwrap();
+        assert!(actual
+            .ends_with("Warning: Assets != Liabilities + Equity in USD\n"));
+    }
+}
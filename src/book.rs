@@ -1,27 +1,185 @@
 use crate::{
     balance::Balance,
-    move_::{Move, Side},
+    balance_sheet::{self, AccountClass},
+    checkpoint::{Checkpoint, Journal},
+    event::Event,
+    income_statement::IncomeStatement,
+    merge_report::MergeReport,
+    move_::{Move, MoveSpec, Side},
+    query::Query,
+    remove_report::RemoveReport,
     sum::Sum,
     transaction::{MoveIndex, Transaction},
+    violation::Violation,
 };
-use slotmap::{new_key_type, DenseSlotMap};
-use std::ops::{Add, AddAssign, Sub, SubAssign};
+use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    convert::{TryFrom, TryInto},
+    error, fmt,
+    ops::{Add, AddAssign, IndexMut, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+use slotmap::{new_key_type, DenseSlotMap, Key, KeyData, SecondaryMap};
 new_key_type! {
     /// A key type for referencing accounts.
+    ///
+    /// Keys are versioned, so a key of a removed account is not reused for
+    /// accounts inserted later.
+    ///
+    /// A key is unique among the accounts of a book, only. It displays as an
+    /// index and a version, such as `1v1`, which parses back into the key.
     pub struct AccountKey;
 }
+impl fmt::Display for AccountKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ffi = self.data().as_ffi();
+        write!(f, "{}v{}", ffi & 0xffff_ffff, ffi >> 32)
+    }
+}
+impl FromStr for AccountKey {
+    type Err = ParseAccountKeyError;
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (index, version) =
+            text.split_once('v').ok_or(ParseAccountKeyError)?;
+        let index = index.parse::<u32>().map_err(|_| ParseAccountKeyError)?;
+        let version =
+            version.parse::<u32>().map_err(|_| ParseAccountKeyError)?;
+        if version % 2 == 0 {
+            return Err(ParseAccountKeyError);
+        }
+        Ok(
+            KeyData::from_ffi(u64::from(version) << 32 | u64::from(index))
+                .into(),
+        )
+    }
+}
+/// The error returned when parsing an [AccountKey] fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseAccountKeyError;
+impl fmt::Display for ParseAccountKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid account key")
+    }
+}
+impl error::Error for ParseAccountKeyError {}
+type Observer = Box<dyn FnMut(&Event) + Send + Sync>;
 /// Represents a book.
+///
+/// A book is [Send] and [Sync] when all of its type parameters are, so it can
+/// be shared between threads, for example in an [Arc](std::sync::Arc), or an
+/// [RwLock](std::sync::RwLock) for mutation.
+///
+/// With the `serde` feature, a book can be serialized and deserialized,
+/// except for its observers, which are not kept.
+/// Deserialization does not check the integrity of the book, so a
+/// deserialized book must be checked with [Book::validate] before use.
+/// With the `bincode` feature, `Book::from_bytes` does so.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Book<Unit, SumNumber, AccountExtra, TransactionExtra, MoveExtra>
 where
     Unit: Ord,
 {
     accounts: DenseSlotMap<AccountKey, AccountExtra>,
+    account_tags: SecondaryMap<AccountKey, BTreeSet<String>>,
     transactions:
         Vec<Transaction<Unit, SumNumber, TransactionExtra, MoveExtra>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    observers: Vec<Observer>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    journal: Journal,
+}
+impl<Unit, SumNumber, AccountExtra, TransactionExtra, MoveExtra> fmt::Debug
+    for Book<Unit, SumNumber, AccountExtra, TransactionExtra, MoveExtra>
+where
+    Unit: Ord + fmt::Debug,
+    SumNumber: fmt::Debug,
+    AccountExtra: fmt::Debug,
+    TransactionExtra: fmt::Debug,
+    MoveExtra: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Book")
+            .field(
+                "accounts",
+                &self.accounts.iter().collect::<BTreeMap<_, _>>(),
+            )
+            .field(
+                "account_tags",
+                &self.account_tags.iter().collect::<BTreeMap<_, _>>(),
+            )
+            .field("transactions", &self.transactions)
+            .finish_non_exhaustive()
+    }
 }
-
 /// Used to index transactions in the book.
+///
+/// Unlike an [AccountKey], a transaction index is a position, rather than a
+/// stable identifier. Inserting or removing a transaction shifts the indexes
+/// of the transactions after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct TransactionIndex(pub usize);
+/// The error returned when inserting an account fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InsertAccountError {
+    /// An account with the same name is already in the book.
+    DuplicateName {
+        /// The key of the account that has the name.
+        account_key: AccountKey,
+    },
+}
+impl fmt::Display for InsertAccountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateName { account_key } => {
+                write!(f, "account {:?} already has the same name", account_key)
+            }
+        }
+    }
+}
+impl error::Error for InsertAccountError {}
+/// The error returned when decoding a [Book] from bytes fails.
+#[cfg(feature = "bincode")]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The bytes are not an encoded book.
+    Bincode(bincode::Error),
+    /// The decoded book has problems, as found by [Book::validate].
+    Invalid(Vec<Violation>),
+}
+#[cfg(feature = "bincode")]
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Bincode(error) => {
+                write!(f, "failed to decode book: {}", error)
+            }
+            DecodeError::Invalid(violations) => write!(
+                f,
+                "decoded book is invalid: {} problems found",
+                violations.len()
+            ),
+        }
+    }
+}
+#[cfg(feature = "bincode")]
+impl error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            DecodeError::Bincode(error) => Some(error),
+            DecodeError::Invalid(_) => None,
+        }
+    }
+}
 impl<Unit, SumNumber, AccountExtra, TransactionExtra, MoveExtra> Default
     for Book<Unit, SumNumber, AccountExtra, TransactionExtra, MoveExtra>
 where
@@ -30,7 +188,10 @@ where
     fn default() -> Self {
         Self {
             accounts: DenseSlotMap::with_key(),
+            account_tags: SecondaryMap::new(),
             transactions: Vec::new(),
+            observers: Vec::new(),
+            journal: Journal::default(),
         }
     }
 }
@@ -41,7 +202,37 @@ where
 {
     /// Inserts an account.
     pub fn insert_account(&mut self, extra: AccountExtra) -> AccountKey {
-        self.accounts.insert(extra)
+        let account_key = self.accounts.insert(extra);
+        self.notify(Event::AccountInserted(account_key));
+        account_key
+    }
+    /// Inserts an account with default extra data.
+    pub fn insert_account_default(&mut self) -> AccountKey
+    where
+        AccountExtra: Default,
+    {
+        self.insert_account(Default::default())
+    }
+    /// Inserts an account, unless an account with the same name is already
+    /// in the book.
+    ///
+    /// `name_of` provides the name of an account from its extra data.
+    pub fn try_insert_account(
+        &mut self,
+        extra: AccountExtra,
+        name_of: impl Fn(&AccountExtra) -> &str,
+    ) -> Result<AccountKey, InsertAccountError> {
+        let name = name_of(&extra);
+        match self
+            .accounts
+            .iter()
+            .find(|(_, account)| name_of(account) == name)
+        {
+            Some((account_key, _)) => {
+                Err(InsertAccountError::DuplicateName { account_key })
+            }
+            None => Ok(self.insert_account(extra)),
+        }
     }
     /// Creates a transaction and inserts it at an index.
     ///
@@ -61,7 +252,22 @@ where
                 extra,
                 moves: Vec::new(),
             },
-        )
+        );
+        self.notify(Event::TransactionInserted(transaction_index));
+    }
+    /// Creates a transaction with default extra data and inserts it at an
+    /// index.
+    ///
+    /// ## Panics
+    ///
+    /// - `transaction_index` out of bounds.
+    pub fn insert_transaction_default(
+        &mut self,
+        transaction_index: TransactionIndex,
+    ) where
+        TransactionExtra: Default,
+    {
+        self.insert_transaction(transaction_index, Default::default())
     }
     /// Creates a new move and inserts it into a transaction at an index.
     ///
@@ -90,11 +296,309 @@ where
         );
         let move_ =
             Move::new(debit_account_key, credit_account_key, sum, extra);
-        let transaction = std::ops::IndexMut::index_mut(
-            &mut self.transactions,
-            transaction_index.0,
-        );
+        let transaction =
+            IndexMut::index_mut(&mut self.transactions, transaction_index.0);
         transaction.moves.insert(move_index.0, move_);
+        self.notify(Event::MoveInserted(transaction_index, move_index));
+    }
+    /// Creates a new move from a signed amount of a unit and inserts it into
+    /// a transaction at an index.
+    ///
+    /// A non-negative `amount` moves into `account_key` from
+    /// `contra_account_key`. A negative `amount` moves out of `account_key`
+    /// into `contra_account_key`. The sum of the move has the absolute
+    /// `amount`.
+    ///
+    /// ## Panics
+    ///
+    /// - The absolute `amount` does not fit in `SumNumber`.
+    /// - Same as [Book::insert_move].
+    #[allow(clippy::too_many_arguments)]
+    pub fn insert_move_signed<SignedNumber>(
+        &mut self,
+        transaction_index: TransactionIndex,
+        move_index: MoveIndex,
+        account_key: AccountKey,
+        contra_account_key: AccountKey,
+        unit: Unit,
+        amount: SignedNumber,
+        extra: MoveExtra,
+    ) where
+        SignedNumber: Default
+            + PartialOrd
+            + Neg<Output = SignedNumber>
+            + TryInto<SumNumber>,
+    {
+        let (debit_account_key, credit_account_key, amount) =
+            if amount < SignedNumber::default() {
+                (account_key, contra_account_key, -amount)
+            } else {
+                (contra_account_key, account_key, amount)
+            };
+        let amount = amount.try_into().unwrap_or_else(|_| {
+            panic!("Amount does not fit in the sum number type.")
+        });
+        let mut sum = Sum(BTreeMap::new());
+        sum.set_amount_for_unit(amount, unit);
+        self.insert_move(
+            transaction_index,
+            move_index,
+            debit_account_key,
+            credit_account_key,
+            sum,
+            extra,
+        );
+    }
+    /// Inserts a transaction at index 0 with the moves that give an account an
+    /// opening balance, against an equity account.
+    ///
+    /// Positive amounts of `opening_balance` move into `account_key` from
+    /// `equity_account_key` and negative amounts move out of `account_key`
+    /// into `equity_account_key`, each in a single move. Amounts of zero are
+    /// left out.
+    ///
+    /// ## Panics
+    ///
+    /// - The absolute value of some amount does not fit in `SumNumber`.
+    /// - Same as [Book::insert_move].
+    pub fn insert_opening_balance<SignedNumber>(
+        &mut self,
+        account_key: AccountKey,
+        equity_account_key: AccountKey,
+        opening_balance: &Balance<Unit, SignedNumber>,
+        transaction_extra: TransactionExtra,
+        move_extra: MoveExtra,
+    ) where
+        Unit: Clone,
+        SignedNumber: Default
+            + PartialOrd
+            + Clone
+            + Neg<Output = SignedNumber>
+            + TryInto<SumNumber>,
+        MoveExtra: Clone,
+    {
+        let moves = Self::balance_moves(
+            account_key,
+            equity_account_key,
+            opening_balance,
+            move_extra,
+        );
+        self.insert_transaction(TransactionIndex(0), transaction_extra);
+        self.insert_moves(TransactionIndex(0), MoveIndex(0), moves);
+    }
+    /// Inserts a transaction after all others with the moves that bring the
+    /// balance of an account to zero, against another account, and returns
+    /// the index of the transaction.
+    ///
+    /// This closes an income or expense account into equity at the end of a
+    /// period. Positive amounts of the balance move out of `account_key`
+    /// into `into_account_key` and negative amounts move into `account_key`
+    /// from `into_account_key`, each in a single move. Amounts of zero are
+    /// left out.
+    ///
+    /// ## Panics
+    ///
+    /// - The absolute value of some amount does not fit in `SumNumber`.
+    /// - Same as [Book::insert_move].
+    pub fn close_account<SignedNumber>(
+        &mut self,
+        account_key: AccountKey,
+        into_account_key: AccountKey,
+        transaction_extra: TransactionExtra,
+        move_extra: MoveExtra,
+    ) -> TransactionIndex
+    where
+        Unit: Clone,
+        SignedNumber: Default
+            + PartialOrd
+            + Clone
+            + Add<Output = SignedNumber>
+            + Sub<Output = SignedNumber>
+            + Neg<Output = SignedNumber>
+            + TryInto<SumNumber>,
+        SumNumber: Clone + Into<SignedNumber>,
+        MoveExtra: Clone,
+    {
+        let balance = -self.account_balance::<SignedNumber>(account_key);
+        let moves = Self::balance_moves(
+            account_key,
+            into_account_key,
+            &balance,
+            move_extra,
+        );
+        let transaction_index = TransactionIndex(self.transactions.len());
+        self.insert_transaction(transaction_index, transaction_extra);
+        self.insert_moves(transaction_index, MoveIndex(0), moves);
+        transaction_index
+    }
+    /// Creates at most two moves that change the balance of `account_key` by
+    /// `balance`, against `contra_account_key`.
+    fn balance_moves<SignedNumber>(
+        account_key: AccountKey,
+        contra_account_key: AccountKey,
+        balance: &Balance<Unit, SignedNumber>,
+        move_extra: MoveExtra,
+    ) -> Vec<MoveSpec<Unit, SumNumber, MoveExtra>>
+    where
+        Unit: Clone,
+        SignedNumber: Default
+            + PartialOrd
+            + Clone
+            + Neg<Output = SignedNumber>
+            + TryInto<SumNumber>,
+        MoveExtra: Clone,
+    {
+        let (positive, negative) = balance.split_positive_negative();
+        let to_sum = |balance: &Balance<Unit, SignedNumber>| {
+            Sum::try_from(balance).unwrap_or_else(|_| {
+                panic!("Amount does not fit in the sum number type.")
+            })
+        };
+        let moves = [
+            (contra_account_key, account_key, to_sum(&positive)),
+            (account_key, contra_account_key, to_sum(&-negative)),
+        ];
+        IntoIterator::into_iter(moves)
+            .filter(|(_, _, sum)| !sum.0.is_empty())
+            .map(|(debit_account_key, credit_account_key, sum)| MoveSpec {
+                debit_account_key,
+                credit_account_key,
+                sum,
+                extra: move_extra.clone(),
+            })
+            .collect()
+    }
+    /// Creates new moves and inserts them into a transaction, in order,
+    /// starting at an index.
+    ///
+    /// Since every move is balanced by itself, so is any batch of moves.
+    ///
+    /// ## Panics
+    ///
+    /// - `transaction_index` out of bounds.
+    /// - `move_index` out of bounds.
+    /// - Same as [Book::insert_move], for each of the `moves`.
+    pub fn insert_moves(
+        &mut self,
+        transaction_index: TransactionIndex,
+        move_index: MoveIndex,
+        moves: Vec<MoveSpec<Unit, SumNumber, MoveExtra>>,
+    ) where
+        Unit: Ord,
+    {
+        moves.into_iter().enumerate().for_each(|(offset, move_)| {
+            self.insert_move(
+                TransactionIndex(transaction_index.0),
+                MoveIndex(move_index.0 + offset),
+                move_.debit_account_key,
+                move_.credit_account_key,
+                move_.sum,
+                move_.extra,
+            );
+        });
+    }
+    /// Creates a move that reverses an existing move and inserts it into a
+    /// transaction at an index.
+    ///
+    /// The reversing move has the same sum as the existing move, with its
+    /// debit and credit accounts swapped, so the net effect of both moves on
+    /// balances is zero.
+    ///
+    /// ## Panics
+    ///
+    /// - `transaction_index` out of bounds.
+    /// - `move_index` out of bounds.
+    /// - `reversal_transaction_index` out of bounds.
+    /// - `reversal_move_index` out of bounds.
+    pub fn reverse_move(
+        &mut self,
+        transaction_index: TransactionIndex,
+        move_index: MoveIndex,
+        reversal_transaction_index: TransactionIndex,
+        reversal_move_index: MoveIndex,
+        extra: MoveExtra,
+    ) where
+        Unit: Ord + Clone,
+        SumNumber: Clone,
+    {
+        let move_ = &self.transactions[transaction_index.0].moves[move_index.0];
+        let debit_account_key = move_.credit_account_key;
+        let credit_account_key = move_.debit_account_key;
+        let sum = move_.sum.clone();
+        self.insert_move(
+            reversal_transaction_index,
+            reversal_move_index,
+            debit_account_key,
+            credit_account_key,
+            sum,
+            extra,
+        );
+    }
+    /// Merges another book into this one.
+    ///
+    /// The accounts of `other` are inserted, in order, under new keys, along
+    /// with their tags.
+    /// The transactions of `other` are inserted, in order, after the existing
+    /// transactions, with their moves referring to the newly inserted
+    /// accounts.
+    /// Observers registered on `other` are dropped.
+    ///
+    /// ## Panics
+    ///
+    /// - Some move or tag of `other` refers to an account that is not in
+    ///   `other`.
+    pub fn merge(&mut self, other: Self) -> MergeReport {
+        other
+            .transactions
+            .iter()
+            .flat_map(|transaction| &transaction.moves)
+            .flat_map(|move_| {
+                vec![move_.debit_account_key, move_.credit_account_key]
+            })
+            .chain(other.account_tags.keys())
+            .for_each(|account_key| other.assert_has_account(account_key));
+        let account_keys = other
+            .accounts
+            .into_iter()
+            .map(|(merged_key, extra)| (merged_key, self.insert_account(extra)))
+            .collect::<BTreeMap<_, _>>();
+        other
+            .account_tags
+            .into_iter()
+            .for_each(|(merged_key, tags)| {
+                tags.iter().for_each(|tag| {
+                    self.add_account_tag(account_keys[&merged_key], tag)
+                })
+            });
+        let first_transaction_index = TransactionIndex(self.transactions.len());
+        other.transactions.into_iter().enumerate().for_each(
+            |(offset, transaction)| {
+                let transaction_index =
+                    TransactionIndex(first_transaction_index.0 + offset);
+                self.insert_transaction(transaction_index, transaction.extra);
+                transaction.moves.into_iter().enumerate().for_each(
+                    |(move_index, move_)| {
+                        self.insert_move(
+                            transaction_index,
+                            MoveIndex(move_index),
+                            account_keys[&move_.debit_account_key],
+                            account_keys[&move_.credit_account_key],
+                            move_.sum,
+                            move_.extra,
+                        );
+                    },
+                );
+            },
+        );
+        MergeReport {
+            account_keys,
+            first_transaction_index,
+        }
+    }
+    /// Checks whether an account is in the book, which it is not after it is
+    /// removed.
+    pub fn contains_account(&self, account_key: AccountKey) -> bool {
+        self.accounts.contains_key(account_key)
     }
     /// Gets an account using a key.
     ///
@@ -111,6 +615,175 @@ where
     ) -> impl Iterator<Item = (AccountKey, &AccountExtra)> {
         self.accounts.iter()
     }
+    /// Finds the first account, in the order of [Book::accounts], for which
+    /// `predicate` returns `true`.
+    pub fn find_account(
+        &self,
+        predicate: impl Fn(&AccountExtra) -> bool,
+    ) -> Option<(AccountKey, &AccountExtra)> {
+        self.accounts().find(|(_, extra)| predicate(extra))
+    }
+    /// Adds a tag to an account.
+    ///
+    /// Tags are labels for grouping accounts, independently of their extra
+    /// data. An account may have any number of tags.
+    ///
+    /// ## Panics
+    ///
+    /// - `account_key` is not in the book.
+    pub fn add_account_tag(&mut self, account_key: AccountKey, tag: &str) {
+        self.assert_has_account(account_key);
+        self.account_tags
+            .entry(account_key)
+            .unwrap()
+            .or_default()
+            .insert(tag.to_owned());
+        self.notify(Event::AccountTagAdded(account_key));
+    }
+    /// Removes a tag from an account.
+    ///
+    /// Returns whether the account had the tag.
+    ///
+    /// ## Panics
+    ///
+    /// - `account_key` is not in the book.
+    pub fn remove_account_tag(
+        &mut self,
+        account_key: AccountKey,
+        tag: &str,
+    ) -> bool {
+        self.assert_has_account(account_key);
+        let removed = self
+            .account_tags
+            .get_mut(account_key)
+            .is_some_and(|tags| tags.remove(tag));
+        if removed {
+            self.notify(Event::AccountTagRemoved(account_key));
+        }
+        removed
+    }
+    /// Checks whether an account has a tag.
+    ///
+    /// ## Panics
+    ///
+    /// - `account_key` is not in the book.
+    pub fn account_has_tag(&self, account_key: AccountKey, tag: &str) -> bool {
+        self.assert_has_account(account_key);
+        self.account_tags
+            .get(account_key)
+            .is_some_and(|tags| tags.contains(tag))
+    }
+    /// Gets an iterator of the accounts that have a tag, in the order of
+    /// [Book::accounts].
+    pub fn accounts_with_tag<'a>(
+        &'a self,
+        tag: &'a str,
+    ) -> impl Iterator<Item = (AccountKey, &'a AccountExtra)> {
+        self.accounts().filter(move |(account_key, _)| {
+            self.account_tags
+                .get(*account_key)
+                .is_some_and(|tags| tags.contains(tag))
+        })
+    }
+    /// Gets the keys of an account and its ancestors, from the root down to
+    /// the account.
+    ///
+    /// `parent_of` provides the parent of an account, if any, for example
+    /// from its extra data.
+    ///
+    /// ## Panics
+    ///
+    /// - `account_key` or some ancestor is not in the book.
+    /// - The parents form a cycle.
+    pub fn account_ancestry(
+        &self,
+        account_key: AccountKey,
+        parent_of: impl Fn(AccountKey, &AccountExtra) -> Option<AccountKey>,
+    ) -> Vec<AccountKey> {
+        let mut ancestry = Vec::new();
+        let mut current = Some(account_key);
+        while let Some(account_key) = current {
+            self.assert_has_account(account_key);
+            assert!(
+                ancestry.len() < self.accounts.len(),
+                "Account parents form a cycle."
+            );
+            ancestry.push(account_key);
+            current = parent_of(account_key, &self.accounts[account_key]);
+        }
+        ancestry.reverse();
+        ancestry
+    }
+    /// Gets the number of ancestors of an account, which is zero for a root
+    /// account.
+    ///
+    /// See [Book::account_ancestry].
+    pub fn account_depth(
+        &self,
+        account_key: AccountKey,
+        parent_of: impl Fn(AccountKey, &AccountExtra) -> Option<AccountKey>,
+    ) -> usize {
+        self.account_ancestry(account_key, parent_of).len() - 1
+    }
+    /// Gets the full name of an account, such as `Assets:Bank:Checking`, by
+    /// joining the names of its ancestors and its own with `separator`.
+    ///
+    /// `name_of` provides the name of an account. See
+    /// [Book::account_ancestry].
+    pub fn account_path(
+        &self,
+        account_key: AccountKey,
+        parent_of: impl Fn(AccountKey, &AccountExtra) -> Option<AccountKey>,
+        name_of: impl Fn(&AccountExtra) -> &str,
+        separator: &str,
+    ) -> String {
+        self.account_ancestry(account_key, parent_of)
+            .into_iter()
+            .map(|account_key| name_of(&self.accounts[account_key]))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+    /// Checks whether any move involves an account.
+    ///
+    /// ## Panics
+    ///
+    /// - `account_key` is not in the book.
+    pub fn account_has_moves(&self, account_key: AccountKey) -> bool {
+        self.assert_has_account(account_key);
+        self.indexed_moves().any(|(_, _, move_)| {
+            move_.debit_account_key == account_key
+                || move_.credit_account_key == account_key
+        })
+    }
+    /// Gets the number of accounts.
+    pub fn account_count(&self) -> usize {
+        self.accounts.len()
+    }
+    /// Gets the number of transactions.
+    pub fn transaction_count(&self) -> usize {
+        self.transactions.len()
+    }
+    /// Gets the number of moves in all transactions.
+    pub fn move_count(&self) -> usize {
+        self.transactions
+            .iter()
+            .map(|transaction| transaction.moves.len())
+            .sum()
+    }
+    /// Gets the number of distinct units in the sums of all moves.
+    pub fn unit_count(&self) -> usize {
+        self.units().len()
+    }
+    /// Gets the distinct units in the sums of all moves.
+    pub fn units(&self) -> BTreeSet<&Unit> {
+        self.indexed_moves()
+            .flat_map(|(_, _, move_)| move_.sum.0.keys())
+            .collect()
+    }
+    /// Checks whether a unit is in the sum of some move.
+    pub fn contains_unit(&self, unit: &Unit) -> bool {
+        self.unit_moves(unit).next().is_some()
+    }
     /// Gets an iterator of existing transactions in their order.
     pub fn transactions(
         &self,
@@ -136,6 +809,7 @@ where
     ) {
         self.assert_has_account(account_key);
         *self.accounts.get_mut(account_key).unwrap() = extra;
+        self.notify(Event::AccountSet(account_key));
     }
     /// Sets extra data for a transaction.
     ///
@@ -150,6 +824,7 @@ where
             .get_mut(transaction_index.0)
             .unwrap()
             .extra = extra;
+        self.notify(Event::TransactionExtraSet(transaction_index));
     }
     /// Sets extra data for a move.
     ///
@@ -164,23 +839,23 @@ where
     ) where
         Unit: Ord,
     {
-        let transaction = std::ops::IndexMut::index_mut(
-            &mut self.transactions,
-            transaction_index.0,
-        );
+        let transaction =
+            IndexMut::index_mut(&mut self.transactions, transaction_index.0);
         let move_ = &mut transaction.moves[move_index.0];
         move_.extra = extra;
+        self.notify(Event::MoveExtraSet(transaction_index, move_index));
     }
     /// Calculates the balance of an account at a provided transaction.
     ///
-    /// Providing an out of bounds `transaction_index` is undefined behavior.
+    /// The balance includes the moves of all transactions prior to and
+    /// including the provided transaction.
     ///
     /// ## Panics
     ///
     /// - `account_key` is not in the book.
-    #[allow(clippy::type_complexity)]
-    pub fn account_balance_at_transaction<'a, BalanceNumber>(
-        &'a self,
+    /// - `transaction_index` out of bounds.
+    pub fn account_balance_at_transaction<BalanceNumber>(
+        &self,
         account_key: AccountKey,
         transaction_index: TransactionIndex,
     ) -> Balance<Unit, BalanceNumber>
@@ -193,52 +868,491 @@ where
         SumNumber: Clone + Into<BalanceNumber>,
     {
         self.assert_has_account(account_key);
-        self.transactions
-            .iter()
-            .take(transaction_index.0 + 1)
-            .flat_map(|transaction| transaction.moves.iter())
-            .filter_map(
-                |move_| -> Option<(
-                    fn(
-                        &mut Balance<Unit, BalanceNumber>,
-                        &'a Sum<Unit, SumNumber>,
-                    ),
-                    &Sum<Unit, SumNumber>,
-                )> {
-                    if move_.debit_account_key == account_key {
-                        Some((SubAssign::sub_assign, &move_.sum))
-                    } else if move_.credit_account_key == account_key {
-                        Some((AddAssign::add_assign, &move_.sum))
-                    } else {
-                        None
-                    }
-                },
-            )
-            .fold(
-                <Balance<Unit, BalanceNumber> as Default>::default(),
-                |mut balance, (operation, sum)| {
-                    operation(&mut balance, sum);
-                    balance
-                },
-            )
+        Self::fold_account_balance(
+            account_key,
+            &self.transactions[..=transaction_index.0],
+        )
     }
-    /// Removes an existing transaction from the book.
+    /// Calculates the balance of an account after all transactions.
     ///
     /// ## Panics
     ///
-    /// - `transaction_index` out of bounds.
-    pub fn remove_transaction(&mut self, transaction_index: TransactionIndex) {
-        self.transactions.remove(transaction_index.0);
+    /// - `account_key` is not in the book.
+    pub fn account_balance<BalanceNumber>(
+        &self,
+        account_key: AccountKey,
+    ) -> Balance<Unit, BalanceNumber>
+    where
+        Unit: Ord + Clone,
+        BalanceNumber: Default
+            + Sub<Output = BalanceNumber>
+            + Add<Output = BalanceNumber>
+            + Clone,
+        SumNumber: Clone + Into<BalanceNumber>,
+    {
+        self.assert_has_account(account_key);
+        Self::fold_account_balance(account_key, &self.transactions)
     }
-    /// Removes an existing move from the book.
-    ///
-    /// ## Panics
+    /// Calculates the balances of all accounts after all transactions.
     ///
-    /// - `transaction_index` out of bounds.
-    /// - `move_index` out of bounds.
-    pub fn remove_move(
-        &mut self,
-        transaction_index: TransactionIndex,
+    /// Unlike calling [Book::account_balance] for each account, this goes
+    /// over the moves only once. Accounts without moves have an empty
+    /// balance.
+    pub fn account_balances<BalanceNumber>(
+        &self,
+    ) -> BTreeMap<AccountKey, Balance<Unit, BalanceNumber>>
+    where
+        Unit: Ord + Clone,
+        BalanceNumber: Default
+            + Sub<Output = BalanceNumber>
+            + Add<Output = BalanceNumber>
+            + Clone,
+        SumNumber: Clone + Into<BalanceNumber>,
+    {
+        let balances = self
+            .accounts
+            .keys()
+            .map(|account_key| (account_key, Balance::default()))
+            .collect::<BTreeMap<_, _>>();
+        self.indexed_moves()
+            .fold(balances, |mut balances, (_, _, move_)| {
+                if let Some(balance) =
+                    balances.get_mut(&move_.debit_account_key)
+                {
+                    *balance -= &move_.sum;
+                }
+                if let Some(balance) =
+                    balances.get_mut(&move_.credit_account_key)
+                {
+                    *balance += &move_.sum;
+                }
+                balances
+            })
+    }
+    /// Calculates the amount of a unit in the balance of an account after all
+    /// transactions.
+    ///
+    /// Amounts of other units are skipped.
+    ///
+    /// ## Panics
+    ///
+    /// - `account_key` is not in the book.
+    pub fn account_unit_balance<BalanceNumber>(
+        &self,
+        account_key: AccountKey,
+        unit: &Unit,
+    ) -> BalanceNumber
+    where
+        BalanceNumber:
+            Default + Sub<Output = BalanceNumber> + Add<Output = BalanceNumber>,
+        SumNumber: Clone + Into<BalanceNumber>,
+    {
+        self.assert_has_account(account_key);
+        self.unit_moves(unit).fold(
+            BalanceNumber::default(),
+            |balance, (_, _, move_)| {
+                let amount = move_.sum.0[unit].clone().into();
+                if move_.debit_account_key == account_key {
+                    balance - amount
+                } else if move_.credit_account_key == account_key {
+                    balance + amount
+                } else {
+                    balance
+                }
+            },
+        )
+    }
+    /// Calculates the combined balance of some accounts after all
+    /// transactions.
+    ///
+    /// This is the total of the balances of the accounts, each counted once.
+    /// Moves between two of the accounts cancel out.
+    ///
+    /// ## Panics
+    ///
+    /// - Some of `account_keys` are not in the book.
+    pub fn combined_balance<BalanceNumber>(
+        &self,
+        account_keys: &[AccountKey],
+    ) -> Balance<Unit, BalanceNumber>
+    where
+        Unit: Ord + Clone,
+        BalanceNumber: Default
+            + Sub<Output = BalanceNumber>
+            + Add<Output = BalanceNumber>
+            + Clone,
+        SumNumber: Clone + Into<BalanceNumber>,
+    {
+        let account_keys = account_keys
+            .iter()
+            .map(|account_key| {
+                self.assert_has_account(*account_key);
+                *account_key
+            })
+            .collect::<BTreeSet<_>>();
+        self.indexed_moves().fold(
+            Balance::default(),
+            |mut balance, (_, _, move_)| {
+                if account_keys.contains(&move_.debit_account_key) {
+                    balance -= &move_.sum;
+                }
+                if account_keys.contains(&move_.credit_account_key) {
+                    balance += &move_.sum;
+                }
+                balance
+            },
+        )
+    }
+    /// Gets an iterator of the moves of an account, each along with the
+    /// balance of the account right after it.
+    ///
+    /// Moves are iterated in the order of their transactions and, within a
+    /// transaction, in their order.
+    ///
+    /// ## Panics
+    ///
+    /// - `account_key` is not in the book.
+    #[allow(clippy::type_complexity)]
+    pub fn account_statement<BalanceNumber>(
+        &self,
+        account_key: AccountKey,
+    ) -> impl Iterator<
+        Item = (
+            TransactionIndex,
+            MoveIndex,
+            &Move<Unit, SumNumber, MoveExtra>,
+            Balance<Unit, BalanceNumber>,
+        ),
+    >
+    where
+        Unit: Ord + Clone,
+        BalanceNumber: Default
+            + Sub<Output = BalanceNumber>
+            + Add<Output = BalanceNumber>
+            + Clone,
+        SumNumber: Clone + Into<BalanceNumber>,
+    {
+        self.assert_has_account(account_key);
+        self.indexed_moves()
+            .filter(move |(_, _, move_)| {
+                move_.debit_account_key == account_key
+                    || move_.credit_account_key == account_key
+            })
+            .scan(
+                Balance::default(),
+                move |balance, (transaction_index, move_index, move_)| {
+                    if move_.debit_account_key == account_key {
+                        *balance -= &move_.sum;
+                    } else {
+                        *balance += &move_.sum;
+                    }
+                    Some((
+                        transaction_index,
+                        move_index,
+                        move_,
+                        balance.clone(),
+                    ))
+                },
+            )
+    }
+    /// Gets an iterator of the moves from one account to another, in the
+    /// order of their transactions and, within a transaction, in their order.
+    ///
+    /// Only moves with `debit_account_key` as their debit account and
+    /// `credit_account_key` as their credit account are included.
+    ///
+    /// ## Panics
+    ///
+    /// - Either account is not in the book.
+    #[allow(clippy::type_complexity)]
+    pub fn moves_between(
+        &self,
+        debit_account_key: AccountKey,
+        credit_account_key: AccountKey,
+    ) -> impl Iterator<
+        Item = (
+            TransactionIndex,
+            MoveIndex,
+            &Move<Unit, SumNumber, MoveExtra>,
+        ),
+    > {
+        self.assert_has_account(debit_account_key);
+        self.assert_has_account(credit_account_key);
+        self.indexed_moves().filter(move |(_, _, move_)| {
+            move_.debit_account_key == debit_account_key
+                && move_.credit_account_key == credit_account_key
+        })
+    }
+    /// Calculates the total of the moves from one account to another.
+    ///
+    /// See [Book::moves_between].
+    ///
+    /// ## Panics
+    ///
+    /// - Either account is not in the book.
+    pub fn flow_between<BalanceNumber>(
+        &self,
+        debit_account_key: AccountKey,
+        credit_account_key: AccountKey,
+    ) -> Balance<Unit, BalanceNumber>
+    where
+        Unit: Clone,
+        BalanceNumber: Default + Add<Output = BalanceNumber> + Clone,
+        SumNumber: Clone + Into<BalanceNumber>,
+    {
+        self.moves_between(debit_account_key, credit_account_key)
+            .fold(Balance::default(), |balance, (_, _, move_)| {
+                balance + &move_.sum
+            })
+    }
+    /// Calculates the total of the moves from one account to another, for
+    /// every pair of accounts with moves between them.
+    ///
+    /// The keys are pairs of debit and credit account keys. Pairs without
+    /// moves are left out.
+    pub fn flow_graph<BalanceNumber>(
+        &self,
+    ) -> BTreeMap<(AccountKey, AccountKey), Balance<Unit, BalanceNumber>>
+    where
+        Unit: Clone,
+        BalanceNumber: Default + Add<Output = BalanceNumber> + Clone,
+        SumNumber: Clone + Into<BalanceNumber>,
+    {
+        self.indexed_moves().fold(
+            BTreeMap::new(),
+            |mut graph, (_, _, move_)| {
+                *graph
+                    .entry((move_.debit_account_key, move_.credit_account_key))
+                    .or_insert_with(Balance::default) += &move_.sum;
+                graph
+            },
+        )
+    }
+    /// Gets an iterator of the moves whose sum has an amount of a unit, in
+    /// the order of their transactions and, within a transaction, in their
+    /// order.
+    #[allow(clippy::type_complexity)]
+    pub fn unit_moves<'a>(
+        &'a self,
+        unit: &'a Unit,
+    ) -> impl Iterator<
+        Item = (
+            TransactionIndex,
+            MoveIndex,
+            &'a Move<Unit, SumNumber, MoveExtra>,
+        ),
+    > {
+        self.indexed_moves()
+            .filter(move |(_, _, move_)| move_.sum.0.contains_key(unit))
+    }
+    /// Creates a query matching all of the moves in the book.
+    pub fn query(
+        &self,
+    ) -> Query<'_, Unit, SumNumber, AccountExtra, TransactionExtra, MoveExtra>
+    {
+        Query {
+            book: self,
+            account_key: None,
+            unit: None,
+            range: None,
+            predicates: Vec::new(),
+        }
+    }
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn indexed_moves(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            TransactionIndex,
+            MoveIndex,
+            &Move<Unit, SumNumber, MoveExtra>,
+        ),
+    > {
+        self.transactions.iter().enumerate().flat_map(
+            |(transaction_index, transaction)| {
+                transaction.moves.iter().enumerate().map(
+                    move |(move_index, move_)| {
+                        (
+                            TransactionIndex(transaction_index),
+                            MoveIndex(move_index),
+                            move_,
+                        )
+                    },
+                )
+            },
+        )
+    }
+    #[allow(clippy::type_complexity)]
+    fn fold_account_balance<'a, BalanceNumber>(
+        account_key: AccountKey,
+        transactions: &'a [Transaction<
+            Unit,
+            SumNumber,
+            TransactionExtra,
+            MoveExtra,
+        >],
+    ) -> Balance<Unit, BalanceNumber>
+    where
+        Unit: Ord + Clone,
+        BalanceNumber: Default
+            + Sub<Output = BalanceNumber>
+            + Add<Output = BalanceNumber>
+            + Clone,
+        SumNumber: Clone + Into<BalanceNumber>,
+    {
+        transactions
+            .iter()
+            .flat_map(|transaction| transaction.moves.iter())
+            .filter_map(
+                |move_| -> Option<(
+                    fn(
+                        &mut Balance<Unit, BalanceNumber>,
+                        &'a Sum<Unit, SumNumber>,
+                    ),
+                    &Sum<Unit, SumNumber>,
+                )> {
+                    if move_.debit_account_key == account_key {
+                        Some((SubAssign::sub_assign, &move_.sum))
+                    } else if move_.credit_account_key == account_key {
+                        Some((AddAssign::add_assign, &move_.sum))
+                    } else {
+                        None
+                    }
+                },
+            )
+            .fold(
+                <Balance<Unit, BalanceNumber> as Default>::default(),
+                |mut balance, (operation, sum)| {
+                    operation(&mut balance, sum);
+                    balance
+                },
+            )
+    }
+    /// Writes a balance sheet of the accounts as a text table.
+    ///
+    /// `classify` provides the class of an account, or `None` for an account
+    /// that is not in the balance sheet. Accounts are named by their extra
+    /// data.
+    ///
+    /// Each class lists the balances of its accounts and their total, in a
+    /// column per unit. The balances of liability and equity accounts are
+    /// negated. The last line confirms that assets equal liabilities plus
+    /// equity in every unit, or else warns of the units in which they do
+    /// not.
+    pub fn balance_sheet<BalanceNumber>(
+        &self,
+        w: &mut impl fmt::Write,
+        classify: impl Fn(AccountKey, &AccountExtra) -> Option<AccountClass>,
+    ) -> fmt::Result
+    where
+        Unit: Clone + fmt::Display,
+        AccountExtra: fmt::Display,
+        BalanceNumber: Default
+            + Sub<Output = BalanceNumber>
+            + Add<Output = BalanceNumber>
+            + Neg<Output = BalanceNumber>
+            + PartialEq
+            + Clone
+            + fmt::Display,
+        SumNumber: Clone + Into<BalanceNumber>,
+    {
+        let accounts = self
+            .accounts()
+            .filter_map(|(account_key, extra)| {
+                classify(account_key, extra).map(|class| {
+                    (
+                        class,
+                        extra.to_string(),
+                        self.account_balance(account_key),
+                    )
+                })
+            })
+            .collect();
+        balance_sheet::write(w, accounts)
+    }
+    /// Calculates the income and expenses over a range of transactions.
+    ///
+    /// Both `start` and `end` are inclusive.
+    /// Income is what moved out of accounts for which `is_income` returns
+    /// `true` and expenses are what moved into accounts for which
+    /// `is_expense` returns `true`.
+    ///
+    /// ## Panics
+    ///
+    /// - `end` out of bounds.
+    /// - `start` is after `end`.
+    pub fn income_statement<BalanceNumber>(
+        &self,
+        start: TransactionIndex,
+        end: TransactionIndex,
+        is_income: impl Fn(AccountKey, &AccountExtra) -> bool,
+        is_expense: impl Fn(AccountKey, &AccountExtra) -> bool,
+    ) -> IncomeStatement<Unit, BalanceNumber>
+    where
+        Unit: Ord + Clone,
+        BalanceNumber: Default
+            + Sub<Output = BalanceNumber>
+            + Add<Output = BalanceNumber>
+            + Clone,
+        SumNumber: Clone + Into<BalanceNumber>,
+    {
+        assert!(
+            start.0 <= end.0,
+            "Start transaction is after end transaction."
+        );
+        let is_income =
+            |account_key| is_income(account_key, &self.accounts[account_key]);
+        let is_expense =
+            |account_key| is_expense(account_key, &self.accounts[account_key]);
+        self.transactions[start.0..=end.0]
+            .iter()
+            .flat_map(|transaction| transaction.moves.iter())
+            .fold(
+                IncomeStatement {
+                    income: Balance::default(),
+                    expenses: Balance::default(),
+                    net: Balance::default(),
+                },
+                |mut statement, move_| {
+                    let sum = &move_.sum;
+                    if is_income(move_.debit_account_key) {
+                        statement.income += sum;
+                        statement.net += sum;
+                    }
+                    if is_income(move_.credit_account_key) {
+                        statement.income -= sum;
+                        statement.net -= sum;
+                    }
+                    if is_expense(move_.credit_account_key) {
+                        statement.expenses += sum;
+                        statement.net -= sum;
+                    }
+                    if is_expense(move_.debit_account_key) {
+                        statement.expenses -= sum;
+                        statement.net += sum;
+                    }
+                    statement
+                },
+            )
+    }
+    /// Removes an existing transaction from the book.
+    ///
+    /// ## Panics
+    ///
+    /// - `transaction_index` out of bounds.
+    pub fn remove_transaction(&mut self, transaction_index: TransactionIndex) {
+        self.transactions.remove(transaction_index.0);
+        self.notify(Event::TransactionRemoved(transaction_index));
+    }
+    /// Removes an existing move from the book.
+    ///
+    /// ## Panics
+    ///
+    /// - `transaction_index` out of bounds.
+    /// - `move_index` out of bounds.
+    pub fn remove_move(
+        &mut self,
+        transaction_index: TransactionIndex,
         move_index: MoveIndex,
     ) where
         Unit: Ord,
@@ -246,6 +1360,76 @@ where
         self.transactions[transaction_index.0]
             .moves
             .remove(move_index.0);
+        self.notify(Event::MoveRemoved(transaction_index, move_index));
+    }
+    /// Removes the moves that duplicate an earlier move of the same
+    /// transaction and returns them, in their order.
+    ///
+    /// Moves are duplicates when they are equal by [Move::structural_eq],
+    /// regardless of their extra data. The earliest of the duplicates is
+    /// kept.
+    /// Equal moves of different transactions, such as a recurring payment,
+    /// are not duplicates. To find those, see [Book::dedup_moves_by_key].
+    pub fn dedup_moves(&mut self) -> Vec<Move<Unit, SumNumber, MoveExtra>>
+    where
+        SumNumber: Default + PartialEq,
+    {
+        self.dedup_moves_by_key(|transaction_index, _| transaction_index)
+    }
+    /// Removes the moves that duplicate an earlier move with the same key and
+    /// returns them, in their order.
+    ///
+    /// Moves are duplicates when they are equal by [Move::structural_eq],
+    /// regardless of their extra data, and `key` returns the same key for
+    /// them. Moves of different transactions can be duplicates, for example
+    /// when the key is an identifier in the extra data of the moves. The
+    /// earliest of the duplicates is kept.
+    pub fn dedup_moves_by_key<Key>(
+        &mut self,
+        mut key: impl FnMut(
+            TransactionIndex,
+            &Move<Unit, SumNumber, MoveExtra>,
+        ) -> Key,
+    ) -> Vec<Move<Unit, SumNumber, MoveExtra>>
+    where
+        SumNumber: Default + PartialEq,
+        Key: Ord,
+    {
+        let mut kept = BTreeMap::<_, Vec<&Sum<Unit, SumNumber>>>::new();
+        let duplicates = self
+            .indexed_moves()
+            .filter(|(transaction_index, _, move_)| {
+                let sums = kept
+                    .entry((
+                        move_.debit_account_key,
+                        move_.credit_account_key,
+                        key(*transaction_index, move_),
+                    ))
+                    .or_default();
+                let duplicate =
+                    sums.iter().any(|sum| sum.semantic_eq(&move_.sum));
+                if !duplicate {
+                    sums.push(&move_.sum);
+                }
+                duplicate
+            })
+            .map(|(transaction_index, move_index, _)| {
+                (transaction_index, move_index)
+            })
+            .collect::<Vec<_>>();
+        let mut removed = duplicates
+            .into_iter()
+            .rev()
+            .map(|(transaction_index, move_index)| {
+                let move_ = self.transactions[transaction_index.0]
+                    .moves
+                    .remove(move_index.0);
+                self.notify(Event::MoveRemoved(transaction_index, move_index));
+                move_
+            })
+            .collect::<Vec<_>>();
+        removed.reverse();
+        removed
     }
     /// Sets the sum of an existing move.
     ///
@@ -262,6 +1446,55 @@ where
         Unit: Ord,
     {
         self.transactions[transaction_index.0].moves[move_index.0].sum = sum;
+        self.notify(Event::MoveSumSet(transaction_index, move_index));
+    }
+    /// Replaces a unit with another in the sums of all moves.
+    ///
+    /// Emits [Event::MoveSumSet] for every move whose sum had the unit.
+    /// If no move has the unit, or `new_unit` is the unit itself, nothing
+    /// changes and no event is emitted.
+    ///
+    /// ## Panics
+    ///
+    /// - `new_unit` is already in the sum of some move, and is not the unit
+    ///   itself.
+    pub fn rename_unit(&mut self, unit: &Unit, new_unit: Unit)
+    where
+        Unit: Clone,
+    {
+        if *unit == new_unit {
+            return;
+        }
+        assert!(
+            self.indexed_moves()
+                .all(|(_, _, move_)| !move_.sum.0.contains_key(&new_unit)),
+            "Unit is already in the book."
+        );
+        let renamed = self
+            .transactions
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(transaction_index, transaction)| {
+                transaction.moves.iter_mut().enumerate().map(
+                    move |(move_index, move_)| {
+                        (transaction_index, move_index, move_)
+                    },
+                )
+            })
+            .filter_map(|(transaction_index, move_index, move_)| {
+                let amount = move_.sum.0.remove(unit)?;
+                move_.sum.0.insert(new_unit.clone(), amount);
+                Some((
+                    TransactionIndex(transaction_index),
+                    MoveIndex(move_index),
+                ))
+            })
+            .collect::<Vec<_>>();
+        renamed
+            .into_iter()
+            .for_each(|(transaction_index, move_index)| {
+                self.notify(Event::MoveSumSet(transaction_index, move_index));
+            });
     }
     /// Sets the account for one of the sides of an existing move.
     ///
@@ -293,313 +1526,1965 @@ where
                 move_.credit_account_key = account_key;
             }
         }
+        self.notify(Event::MoveSideSet(transaction_index, move_index, side));
     }
-    fn assert_has_account(&self, key: AccountKey) {
-        assert!(
-            self.accounts.contains_key(key),
-            format!("No account found for key {:?}", key),
+    /// Removes an account, along with its tags.
+    ///
+    /// Returns a report of the extra data of the account and the numbers of
+    /// its moves that were reassigned or removed.
+    ///
+    /// Each move of the account is either reassigned to `reassign_to`, on the
+    /// same side, or removed when `reassign_to` is `None`. The other side of a
+    /// reassigned move is left as is, so the move keeps balancing it.
+    ///
+    /// ## Panics
+    ///
+    /// - `account_key` is not in the book.
+    /// - `reassign_to` is not in the book or is `account_key`.
+    /// - Some move is between `account_key` and `reassign_to`.
+    pub fn remove_account(
+        &mut self,
+        account_key: AccountKey,
+        reassign_to: Option<AccountKey>,
+    ) -> RemoveReport<AccountExtra> {
+        self.assert_has_account(account_key);
+        if let Some(reassign_to) = reassign_to {
+            self.assert_has_account(reassign_to);
+            assert_ne!(
+                account_key, reassign_to,
+                "Account is reassigned to itself."
+            );
+            assert!(
+                self.moves_between(account_key, reassign_to)
+                    .chain(self.moves_between(reassign_to, account_key))
+                    .next()
+                    .is_none(),
+                "Some move is between the account and the one it is \
+                 reassigned to."
+            );
+        }
+        let mut reassigned_moves = 0;
+        let mut removed_moves = 0;
+        (0..self.transactions.len()).for_each(|transaction_index| {
+            let move_count = self.transactions[transaction_index].moves.len();
+            (0..move_count).rev().for_each(|move_index| {
+                let move_ =
+                    &mut self.transactions[transaction_index].moves[move_index];
+                let side = if move_.debit_account_key == account_key {
+                    Side::Debit
+                } else if move_.credit_account_key == account_key {
+                    Side::Credit
+                } else {
+                    return;
+                };
+                let transaction_index = TransactionIndex(transaction_index);
+                let move_index = MoveIndex(move_index);
+                let event = match (reassign_to, side) {
+                    (Some(reassign_to), Side::Debit) => {
+                        move_.debit_account_key = reassign_to;
+                        reassigned_moves += 1;
+                        Event::MoveSideSet(transaction_index, move_index, side)
+                    }
+                    (Some(reassign_to), Side::Credit) => {
+                        move_.credit_account_key = reassign_to;
+                        reassigned_moves += 1;
+                        Event::MoveSideSet(transaction_index, move_index, side)
+                    }
+                    (None, _) => {
+                        self.transactions[transaction_index.0]
+                            .moves
+                            .remove(move_index.0);
+                        removed_moves += 1;
+                        Event::MoveRemoved(transaction_index, move_index)
+                    }
+                };
+                self.notify(event);
+            });
+        });
+        self.account_tags.remove(account_key);
+        let extra = self.accounts.remove(account_key).unwrap();
+        self.notify(Event::AccountRemoved(account_key));
+        RemoveReport {
+            extra,
+            reassigned_moves,
+            removed_moves,
+        }
+    }
+    /// Removes all accounts and transactions, keeping the allocated memory
+    /// and the registered observers.
+    ///
+    /// Keys of the removed accounts are not reused, so they do not refer to
+    /// accounts inserted afterwards.
+    pub fn clear(&mut self) {
+        self.accounts.clear();
+        self.account_tags.clear();
+        self.transactions.clear();
+        self.notify(Event::Cleared);
+    }
+    /// Creates a checkpoint of the book, for [Book::restore].
+    ///
+    /// While the checkpoint, or a clone of it, is alive, the book keeps a
+    /// record of the accounts, transactions and moves that are inserted into
+    /// it and removed from it. Once no checkpoint of the book is alive, the
+    /// record is dropped on the next change.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let account_keys = self.accounts.keys().collect();
+        let move_counts = self
+            .transactions
+            .iter()
+            .map(|transaction| transaction.moves.len())
+            .collect();
+        self.journal.checkpoint(account_keys, move_counts)
+    }
+    /// Removes the accounts, transactions and moves that were inserted since
+    /// a checkpoint, wherever they were inserted.
+    ///
+    /// Any other changes, such as to extra data or to the sides of moves, are
+    /// not undone.
+    ///
+    /// Keys of the removed accounts are not reused, so they do not refer to
+    /// accounts inserted afterwards.
+    ///
+    /// ## Panics
+    ///
+    /// - Some account, transaction or move that existed at the checkpoint was
+    ///   removed.
+    /// - Some move that existed at the checkpoint refers to an account that
+    ///   was inserted since.
+    /// - The checkpoint was undone by restoring an earlier one.
+    pub fn restore(&mut self, checkpoint: &Checkpoint) {
+        const TRANSACTION_REMOVED: &str =
+            "Some transaction or move of the checkpoint was removed.";
+        let mut inserted_account_keys = BTreeSet::new();
+        // Whether each move was inserted, or `None` for inserted transactions.
+        let mut inserted_moves = checkpoint
+            .move_counts
+            .iter()
+            .map(|count| Some(vec![false; *count]))
+            .collect::<Vec<_>>();
+        self.journal
+            .since(checkpoint)
+            .for_each(|event| match event {
+                Event::AccountInserted(account_key) => {
+                    inserted_account_keys.insert(account_key);
+                }
+                Event::AccountRemoved(account_key) => assert!(
+                    inserted_account_keys.remove(&account_key),
+                    "Some account of the checkpoint was removed."
+                ),
+                Event::TransactionInserted(transaction_index) => {
+                    inserted_moves.insert(transaction_index.0, None)
+                }
+                Event::TransactionRemoved(transaction_index) => assert!(
+                    inserted_moves.remove(transaction_index.0).is_none(),
+                    "{}",
+                    TRANSACTION_REMOVED
+                ),
+                Event::MoveInserted(transaction_index, move_index) => {
+                    if let Some(moves) =
+                        &mut inserted_moves[transaction_index.0]
+                    {
+                        moves.insert(move_index.0, true);
+                    }
+                }
+                Event::MoveRemoved(transaction_index, move_index) => {
+                    if let Some(moves) =
+                        &mut inserted_moves[transaction_index.0]
+                    {
+                        assert!(
+                            moves.remove(move_index.0),
+                            "{}",
+                            TRANSACTION_REMOVED
+                        );
+                    }
+                }
+                Event::Cleared => {
+                    assert!(
+                        checkpoint.account_keys.is_empty(),
+                        "Some account of the checkpoint was removed."
+                    );
+                    assert!(
+                        inserted_moves.iter().all(Option::is_none),
+                        "{}",
+                        TRANSACTION_REMOVED
+                    );
+                    inserted_account_keys.clear();
+                    inserted_moves.clear();
+                }
+                _ => {}
+            });
+        assert!(
+            checkpoint
+                .account_keys
+                .iter()
+                .all(|account_key| self.accounts.contains_key(*account_key)),
+            "Some account of the checkpoint was removed."
+        );
+        assert!(
+            self.transactions.iter().zip(&inserted_moves).all(
+                |(transaction, inserted)| {
+                    let inserted = match inserted {
+                        Some(inserted) => inserted,
+                        None => return true,
+                    };
+                    transaction.moves.iter().zip(inserted).all(
+                        |(move_, inserted)| {
+                            *inserted
+                                || !inserted_account_keys
+                                    .contains(&move_.debit_account_key)
+                                    && !inserted_account_keys
+                                        .contains(&move_.credit_account_key)
+                        },
+                    )
+                }
+            ),
+            "Some move of the checkpoint refers to an inserted account."
+        );
+        let mut inserted_transactions = inserted_moves.iter();
+        self.transactions
+            .retain(|_| inserted_transactions.next().unwrap().is_some());
+        self.transactions
+            .iter_mut()
+            .zip(inserted_moves.iter().flatten())
+            .for_each(|(transaction, inserted)| {
+                let mut inserted = inserted.iter();
+                transaction.moves.retain(|_| !*inserted.next().unwrap());
+            });
+        inserted_account_keys.iter().for_each(|account_key| {
+            self.accounts.remove(*account_key);
+            self.account_tags.remove(*account_key);
+        });
+        self.journal.truncate(checkpoint);
+        self.notify(Event::Restored);
+    }
+    /// Registers an observer that is called with an [Event] for every change
+    /// made to the book.
+    ///
+    /// Observers are called in order of registration, right after the change
+    /// is made.
+    /// Since observers are not provided access to the book, they cannot make
+    /// changes to it while it is being changed.
+    pub fn on_change(
+        &mut self,
+        observer: impl FnMut(&Event) + Send + Sync + 'static,
+    ) {
+        self.observers.push(Box::new(observer));
+    }
+    fn notify(&mut self, event: Event) {
+        self.journal.record(event);
+        self.observers
+            .iter_mut()
+            .for_each(|observer| observer(&event));
+    }
+    /// Checks the integrity of the book and returns all problems found.
+    ///
+    /// A book that was changed only through its methods has no problems.
+    /// This is meant for books obtained otherwise, such as by
+    /// deserialization.
+    pub fn validate(&self) -> Vec<Violation> {
+        self.indexed_moves()
+            .flat_map(|(transaction_index, move_index, move_)| {
+                let missing_accounts = [
+                    (Side::Debit, move_.debit_account_key),
+                    (Side::Credit, move_.credit_account_key),
+                ]
+                .iter()
+                .filter(|(_, account_key)| {
+                    !self.accounts.contains_key(*account_key)
+                })
+                .map(|(side, account_key)| Violation::MissingAccount {
+                    transaction_index,
+                    move_index,
+                    side: *side,
+                    account_key: *account_key,
+                })
+                .collect::<Vec<_>>();
+                let same_accounts =
+                    if move_.debit_account_key == move_.credit_account_key {
+                        Some(Violation::SameAccounts {
+                            transaction_index,
+                            move_index,
+                        })
+                    } else {
+                        None
+                    };
+                missing_accounts.into_iter().chain(same_accounts)
+            })
+            .chain(
+                self.account_tags
+                    .keys()
+                    .filter(|account_key| {
+                        !self.accounts.contains_key(*account_key)
+                    })
+                    .map(|account_key| Violation::TaggedMissingAccount {
+                        account_key,
+                    }),
+            )
+            .collect()
+    }
+    pub(crate) fn assert_has_account(&self, key: AccountKey) {
+        assert!(
+            self.contains_account(key),
+            "No account found for key {:?}",
+            key,
+        );
+    }
+}
+#[cfg(feature = "bincode")]
+impl<Unit, SumNumber, AccountExtra, TransactionExtra, MoveExtra>
+    Book<Unit, SumNumber, AccountExtra, TransactionExtra, MoveExtra>
+where
+    Unit: Ord,
+{
+    /// Encodes the book into a compact binary form, with [bincode].
+    ///
+    /// Observers are not encoded.
+    ///
+    /// ## Panics
+    ///
+    /// - Serializing some extra data, unit or number fails.
+    pub fn to_bytes(&self) -> Vec<u8>
+    where
+        Self: serde::Serialize,
+    {
+        bincode::serialize(self).expect("Failed to encode book.")
+    }
+    /// Decodes a book from the binary form of [Book::to_bytes].
+    ///
+    /// Fails if the bytes are not an encoded book, or if the decoded book has
+    /// problems found by [Book::validate].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError>
+    where
+        Self: serde::de::DeserializeOwned,
+    {
+        let book: Self =
+            bincode::deserialize(bytes).map_err(DecodeError::Bincode)?;
+        let violations = book.validate();
+        if violations.is_empty() {
+            Ok(book)
+        } else {
+            Err(DecodeError::Invalid(violations))
+        }
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::{
+        AccountKey, Book, InsertAccountError, ParseAccountKeyError,
+        Side::{Credit, Debit},
+        TransactionIndex,
+    };
+    use crate::{event::Event, violation::Violation};
+    use crate::{
+        move_::MoveSpec,
+        test_utils::{append_moves, TestBalance, TestBook},
+        transaction::MoveIndex,
+    };
+    use maplit::btreemap;
+    use proptest::{collection::vec, proptest};
+    use std::{
+        sync::{Arc, Mutex},
+        thread,
+    };
+    #[test]
+    fn default() {
+        let book = TestBook::default();
+        assert!(book.accounts.is_empty());
+        assert!(book.transactions.is_empty());
+    }
+    #[test]
+    fn fmt_debug() {
+        let mut book = TestBook::default();
+        let debit_account_key = book.insert_account("cash");
+        let credit_account_key = book.insert_account("food");
+        let usd = "USD";
+        book.insert_transaction(TransactionIndex(0), "lunch");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            debit_account_key,
+            credit_account_key,
+            sum!(5, usd),
+            "rice",
+        );
+        let actual = format!("{:?}", book);
+        let expected = "Book { \
+            accounts: {AccountKey(1v1): \"cash\", AccountKey(2v1): \"food\"}, \
+            account_tags: {}, \
+            transactions: [Transaction { extra: \"lunch\", moves: [Move { \
+            extra: \"rice\", \
+            debit_account_key: AccountKey(1v1), \
+            credit_account_key: AccountKey(2v1), \
+            sum: Sum({\"USD\": 5}) }] }], .. }";
+        assert_eq!(actual, expected);
+    }
+    #[test]
+    fn send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<TestBook>();
+    }
+    #[test]
+    fn account_balance_concurrently() {
+        let mut book = TestBook::default();
+        let debit_key = book.insert_account("");
+        let credit_key = book.insert_account("");
+        let usd = "USD";
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            debit_key,
+            credit_key,
+            sum!(3, usd),
+            "",
+        );
+        let book = Arc::new(book);
+        let handles = (0..4)
+            .map(|_| {
+                let book = Arc::clone(&book);
+                thread::spawn(move || book.account_balance::<i128>(credit_key))
+            })
+            .collect::<Vec<_>>();
+        handles.into_iter().for_each(|handle| {
+            assert_eq!(
+                handle.join().unwrap(),
+                TestBalance::default() + &sum!(3, usd),
+            );
+        });
+    }
+    #[test]
+    fn insert_account() {
+        let mut book = TestBook::default();
+        book.insert_account("");
+        assert_eq!(book.accounts.len(), 1);
+    }
+    #[test]
+    fn account_key_fmt_display_from_str() {
+        let mut book = TestBook::default();
+        let account_a_key = book.insert_account("a");
+        let account_b_key = book.insert_account("b");
+        book.accounts.remove(account_a_key);
+        let account_c_key = book.insert_account("c");
+        assert_eq!(account_a_key.to_string(), "1v1");
+        assert_eq!(account_c_key.to_string(), "1v3");
+        [account_a_key, account_b_key, account_c_key]
+            .iter()
+            .for_each(|account_key| {
+                assert_eq!(account_key.to_string().parse(), Ok(*account_key));
+            });
+    }
+    #[test]
+    fn account_key_from_str_error() {
+        ["", "1", "v1", "1v", "1v2", "-1v1", "1v1v1"]
+            .iter()
+            .for_each(|text| {
+                assert_eq!(
+                    text.parse::<AccountKey>(),
+                    Err(ParseAccountKeyError),
+                    "{:?}",
+                    text,
+                );
+            });
+        assert_eq!(ParseAccountKeyError.to_string(), "invalid account key");
+    }
+    type HierarchyBook =
+        Book<&'static str, u64, (&'static str, Option<AccountKey>), (), ()>;
+    fn hierarchy() -> (HierarchyBook, [AccountKey; 3]) {
+        let mut book = Book::default();
+        let assets = book.insert_account(("Assets", None));
+        let bank = book.insert_account(("Bank", Some(assets)));
+        let checking = book.insert_account(("Checking", Some(bank)));
+        (book, [assets, bank, checking])
+    }
+    fn parent_of(
+        _: AccountKey,
+        extra: &(&str, Option<AccountKey>),
+    ) -> Option<AccountKey> {
+        extra.1
+    }
+    fn name_of<'a>(extra: &'a (&str, Option<AccountKey>)) -> &'a str {
+        extra.0
+    }
+    #[test]
+    fn account_ancestry_depth_path() {
+        let (book, [assets, bank, checking]) = hierarchy();
+        assert_eq!(
+            book.account_ancestry(checking, parent_of),
+            vec![assets, bank, checking],
+        );
+        assert_eq!(book.account_ancestry(assets, parent_of), vec![assets]);
+        assert_eq!(book.account_depth(checking, parent_of), 2);
+        assert_eq!(book.account_depth(assets, parent_of), 0);
+        assert_eq!(
+            book.account_path(checking, parent_of, name_of, ":"),
+            "Assets:Bank:Checking",
+        );
+        assert_eq!(
+            book.account_path(assets, parent_of, name_of, ":"),
+            "Assets"
+        );
+    }
+    #[test]
+    #[should_panic(expected = "Account parents form a cycle.")]
+    fn account_ancestry_cycle() {
+        let (mut book, [assets, _, checking]) = hierarchy();
+        book.set_account(assets, ("Assets", Some(checking)));
+        book.account_ancestry(checking, parent_of);
+    }
+    #[test]
+    fn checkpoint_restore() {
+        let mut book = TestBook::default();
+        let account_a_key = book.insert_account("a");
+        let account_b_key = book.insert_account("b");
+        let usd = "USD";
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            account_a_key,
+            account_b_key,
+            sum!(1, usd),
+            "",
+        );
+        let checkpoint = book.checkpoint();
+        let account_c_key = book.insert_account("c");
+        book.add_account_tag(account_c_key, "new");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(1),
+            account_a_key,
+            account_c_key,
+            sum!(2, usd),
+            "",
+        );
+        book.insert_transaction(TransactionIndex(1), "");
+        book.insert_move(
+            TransactionIndex(1),
+            MoveIndex(0),
+            account_b_key,
+            account_c_key,
+            sum!(4, usd),
+            "",
+        );
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let observed = Arc::clone(&events);
+        book.on_change(move |event| observed.lock().unwrap().push(*event));
+        book.restore(&checkpoint);
+        assert_eq!(*events.lock().unwrap(), vec![Event::Restored]);
+        let restored = book.checkpoint();
+        assert_eq!(restored.account_keys, checkpoint.account_keys);
+        assert_eq!(restored.move_counts, checkpoint.move_counts);
+        assert_eq!(book.account_count(), 2);
+        assert!(!book.account_tags.contains_key(account_c_key));
+        assert_eq!(book.transaction_count(), 1);
+        assert_eq!(book.move_count(), 1);
+        assert_eq!(
+            book.account_balance::<i128>(account_a_key),
+            TestBalance::default() - &sum!(1, usd),
+        );
+        assert_ne!(book.insert_account("d"), account_c_key);
+    }
+    #[test]
+    #[should_panic(expected = "Some account of the checkpoint was removed.")]
+    fn restore_account_removed() {
+        let mut book = TestBook::default();
+        let account_key = book.insert_account("");
+        let checkpoint = book.checkpoint();
+        book.accounts.remove(account_key);
+        book.restore(&checkpoint);
+    }
+    #[test]
+    #[should_panic(
+        expected = "Some transaction or move of the checkpoint was removed."
+    )]
+    fn restore_transaction_removed() {
+        let mut book = TestBook::default();
+        book.insert_transaction(TransactionIndex(0), "");
+        let checkpoint = book.checkpoint();
+        book.remove_transaction(TransactionIndex(0));
+        book.restore(&checkpoint);
+    }
+    #[test]
+    fn restore_inserted_before_checkpoint_contents() {
+        let mut book = TestBook::default();
+        let account_key = book.insert_account("");
+        let equity_key = book.insert_account("");
+        let usd = "USD";
+        book.insert_transaction(TransactionIndex(0), "old");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            account_key,
+            equity_key,
+            sum!(1, usd),
+            "old",
+        );
+        let checkpoint = book.checkpoint();
+        let opening_balance = TestBalance::default() + &sum!(5, usd);
+        book.insert_opening_balance(
+            account_key,
+            equity_key,
+            &opening_balance,
+            "opening",
+            "",
+        );
+        book.insert_move(
+            TransactionIndex(1),
+            MoveIndex(0),
+            equity_key,
+            account_key,
+            sum!(2, usd),
+            "new",
+        );
+        book.restore(&checkpoint);
+        assert_eq!(book.transaction_count(), 1);
+        assert_eq!(book.transactions[0].extra, "old");
+        assert_eq!(book.transactions[0].moves.len(), 1);
+        assert_eq!(book.transactions[0].moves[0].extra, "old");
+    }
+    #[test]
+    fn restore_inserted_and_removed() {
+        let mut book = TestBook::default();
+        let account_a_key = book.insert_account("a");
+        let account_b_key = book.insert_account("b");
+        let usd = "USD";
+        book.insert_transaction(TransactionIndex(0), "old");
+        let checkpoint = book.checkpoint();
+        book.insert_transaction(TransactionIndex(0), "removed");
+        book.insert_transaction(TransactionIndex(0), "new");
+        book.insert_move(
+            TransactionIndex(2),
+            MoveIndex(0),
+            account_a_key,
+            account_b_key,
+            sum!(1, usd),
+            "",
+        );
+        book.remove_transaction(TransactionIndex(1));
+        book.remove_move(TransactionIndex(1), MoveIndex(0));
+        let account_c_key = book.insert_account("c");
+        book.remove_account(account_c_key, None);
+        book.restore(&checkpoint);
+        assert_eq!(book.account_count(), 2);
+        assert_eq!(book.transaction_count(), 1);
+        assert_eq!(book.transactions[0].extra, "old");
+        assert_eq!(book.move_count(), 0);
+        book.insert_account("d");
+        book.restore(&checkpoint);
+        assert_eq!(book.account_count(), 2);
+    }
+    #[test]
+    #[should_panic(
+        expected = "Some move of the checkpoint refers to an inserted account."
+    )]
+    fn restore_move_side_set_to_inserted_account() {
+        let mut book = TestBook::default();
+        let account_a_key = book.insert_account("a");
+        let account_b_key = book.insert_account("b");
+        let usd = "USD";
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            account_a_key,
+            account_b_key,
+            sum!(1, usd),
+            "",
+        );
+        let checkpoint = book.checkpoint();
+        let account_c_key = book.insert_account("c");
+        book.set_move_side(
+            TransactionIndex(0),
+            MoveIndex(0),
+            Credit,
+            account_c_key,
+        );
+        book.restore(&checkpoint);
+    }
+    #[test]
+    #[should_panic(
+        expected = "Some transaction or move of the checkpoint was removed."
+    )]
+    fn restore_cleared() {
+        let mut book = TestBook::default();
+        book.insert_transaction(TransactionIndex(0), "");
+        let checkpoint = book.checkpoint();
+        book.clear();
+        book.restore(&checkpoint);
+    }
+    #[test]
+    #[should_panic(
+        expected = "The checkpoint was undone by restoring an earlier one."
+    )]
+    fn restore_undone_checkpoint() {
+        let mut book = TestBook::default();
+        let earlier = book.checkpoint();
+        book.insert_account("a");
+        let later = book.checkpoint();
+        book.restore(&earlier);
+        book.insert_account("b");
+        book.restore(&later);
+    }
+    #[test]
+    fn try_insert_account() {
+        let mut book = TestBook::default();
+        fn name_of<'a>(extra: &'a &str) -> &'a str {
+            extra
+        }
+        let account_a_key = book.try_insert_account("a", name_of).unwrap();
+        let account_b_key = book.try_insert_account("b", name_of).unwrap();
+        assert_ne!(account_a_key, account_b_key);
+        assert_eq!(
+            book.try_insert_account("a", name_of),
+            Err(InsertAccountError::DuplicateName {
+                account_key: account_a_key,
+            }),
+        );
+        assert_eq!(book.accounts.len(), 2);
+    }
+    #[test]
+    fn insert_account_error_fmt_display() {
+        let mut book = TestBook::default();
+        let account_key = book.insert_account("");
+        let error = InsertAccountError::DuplicateName { account_key };
+        assert_eq!(
+            error.to_string(),
+            format!("account {:?} already has the same name", account_key),
+        );
+    }
+    #[test]
+    fn insert_account_keys_not_reused() {
+        let mut book = TestBook::default();
+        let account_a_key = book.insert_account("a");
+        let account_b_key = book.insert_account("b");
+        book.accounts.remove(account_a_key);
+        let account_c_key = book.insert_account("c");
+        assert_ne!(account_a_key, account_b_key);
+        assert_ne!(account_a_key, account_c_key);
+        assert_ne!(account_b_key, account_c_key);
+        assert!(!book.accounts.contains_key(account_a_key));
+    }
+    #[test]
+    fn insert_account_default() {
+        let mut book = Book::<&str, u64, u8, (), ()>::default();
+        let account_key = book.insert_account_default();
+        assert_eq!(book.accounts[account_key], 0);
+    }
+    #[test]
+    #[should_panic(expected = "insertion index (is 1) should be <= len (is 0)")]
+    fn insert_transaction_panic_index_out_of_bounds() {
+        let mut book = TestBook::default();
+        book.insert_transaction(TransactionIndex(1), "");
+    }
+    #[test]
+    fn insert_transaction() {
+        let mut book = TestBook::default();
+        book.insert_transaction(TransactionIndex(0), "a");
+        book.insert_transaction(TransactionIndex(1), "b");
+        book.insert_transaction(TransactionIndex(0), "c");
+        book.insert_transaction(TransactionIndex(2), "d");
+        assert_eq!(
+            book.transactions
+                .iter()
+                .map(|transaction| transaction.extra())
+                .collect::<Vec<_>>(),
+            [&"c", &"a", &"d", &"b"],
+        );
+    }
+    #[test]
+    fn insert_transaction_default() {
+        let mut book = Book::<&str, u64, (), u8, ()>::default();
+        book.insert_transaction_default(TransactionIndex(0));
+        assert_eq!(book.transactions[0].extra, 0);
+    }
+    #[test]
+    #[should_panic(expected = "insertion index (is 1) should be <= len (is 0)")]
+    fn insert_move_panic_index_out_of_bounds() {
+        let mut book = TestBook::default();
+        let debit_key = book.insert_account("");
+        let credit_key = book.insert_account("");
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(1),
+            debit_key,
+            credit_key,
+            sum!(),
+            "",
+        );
+    }
+    #[test]
+    #[should_panic(expected = "No account found for key ")]
+    fn insert_move_panic_debit_account_not_found() {
+        let mut book = TestBook::default();
+        let debit_key = book.insert_account("");
+        book.accounts.remove(debit_key);
+        let credit_key = book.insert_account("");
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            debit_key,
+            credit_key,
+            sum!(),
+            "",
+        );
+    }
+    #[test]
+    #[should_panic(expected = "No account found for key ")]
+    fn insert_move_panic_credit_account_not_found() {
+        let mut book = TestBook::default();
+        let debit_key = book.insert_account("");
+        let credit_key = book.insert_account("");
+        book.accounts.remove(credit_key);
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            debit_key,
+            credit_key,
+            sum!(),
+            "",
+        );
+    }
+    #[test]
+    fn insert_move() {
+        let mut book = TestBook::default();
+        book.insert_transaction(TransactionIndex(0), "");
+        let debit_key = book.insert_account("");
+        let credit_key = book.insert_account("");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            debit_key,
+            credit_key,
+            sum!(),
+            "a",
+        );
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            debit_key,
+            credit_key,
+            sum!(),
+            "b",
+        );
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(1),
+            debit_key,
+            credit_key,
+            sum!(),
+            "c",
+        );
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(2),
+            debit_key,
+            credit_key,
+            sum!(),
+            "d",
+        );
+        assert_eq!(
+            book.transactions[0]
+                .moves
+                .iter()
+                .map(|move_| move_.extra)
+                .collect::<Vec<_>>(),
+            vec!["b", "c", "d", "a"],
+        );
+    }
+    #[test]
+    #[should_panic(expected = "Amount does not fit in the sum number type.")]
+    fn insert_move_signed_panic_amount_does_not_fit() {
+        let mut book = TestBook::default();
+        let account_key = book.insert_account("");
+        let contra_account_key = book.insert_account("");
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move_signed(
+            TransactionIndex(0),
+            MoveIndex(0),
+            account_key,
+            contra_account_key,
+            "USD",
+            i128::MAX,
+            "",
+        );
+    }
+    #[test]
+    fn insert_move_signed() {
+        let mut book = TestBook::default();
+        let account_key = book.insert_account("");
+        let contra_account_key = book.insert_account("");
+        let usd = "USD";
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move_signed(
+            TransactionIndex(0),
+            MoveIndex(0),
+            account_key,
+            contra_account_key,
+            usd,
+            5,
+            "",
+        );
+        book.insert_move_signed(
+            TransactionIndex(0),
+            MoveIndex(1),
+            account_key,
+            contra_account_key,
+            usd,
+            -3,
+            "",
+        );
+        let positive = &book.transactions[0].moves[0];
+        assert_eq!(positive.debit_account_key, contra_account_key);
+        assert_eq!(positive.credit_account_key, account_key);
+        assert_eq!(positive.sum, sum!(5, usd));
+        let negative = &book.transactions[0].moves[1];
+        assert_eq!(negative.debit_account_key, account_key);
+        assert_eq!(negative.credit_account_key, contra_account_key);
+        assert_eq!(negative.sum, sum!(3, usd));
+    }
+    #[test]
+    fn insert_moves() {
+        let mut book = TestBook::default();
+        let debit_account_key = book.insert_account("");
+        let credit_account_key = book.insert_account("");
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            debit_account_key,
+            credit_account_key,
+            sum!(),
+            "a",
+        );
+        book.insert_moves(
+            TransactionIndex(0),
+            MoveIndex(0),
+            ["b", "c", "d"]
+                .iter()
+                .map(|extra| MoveSpec {
+                    debit_account_key,
+                    credit_account_key,
+                    sum: sum!(),
+                    extra: *extra,
+                })
+                .collect(),
+        );
+        assert_eq!(
+            book.transactions[0]
+                .moves
+                .iter()
+                .map(|move_| move_.extra)
+                .collect::<Vec<_>>(),
+            vec!["b", "c", "d", "a"],
+        );
+    }
+    #[test]
+    #[should_panic(
+        expected = "index out of bounds: the len is 0 but the index is 0"
+    )]
+    fn reverse_move_panic_move_index_out_of_bounds() {
+        let mut book = TestBook::default();
+        book.insert_transaction(TransactionIndex(0), "");
+        book.reverse_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            TransactionIndex(0),
+            MoveIndex(0),
+            "",
+        );
+    }
+    #[test]
+    fn reverse_move() {
+        let mut book = TestBook::default();
+        let debit_key = book.insert_account("");
+        let credit_key = book.insert_account("");
+        let usd = "USD";
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            debit_key,
+            credit_key,
+            sum!(5, usd),
+            "a",
+        );
+        book.insert_transaction(TransactionIndex(1), "");
+        book.reverse_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            TransactionIndex(1),
+            MoveIndex(0),
+            "b",
+        );
+        let reversal = &book.transactions[1].moves[0];
+        assert_eq!(reversal.debit_account_key, credit_key);
+        assert_eq!(reversal.credit_account_key, debit_key);
+        assert_eq!(reversal.sum, sum!(5, usd));
+        assert_eq!(reversal.extra, "b");
+        assert_eq!(book.transactions[0].moves.len(), 1);
+        [debit_key, credit_key].iter().for_each(|account_key| {
+            assert_eq!(
+                book.account_balance::<i128>(*account_key),
+                TestBalance::default() + &sum!(0, usd),
+            );
+        });
+    }
+    #[test]
+    fn merge() {
+        let usd = "USD";
+        let mut book = TestBook::default();
+        let bank_key = book.insert_account("bank");
+        let income_key = book.insert_account("income");
+        book.insert_transaction(TransactionIndex(0), "a");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            income_key,
+            bank_key,
+            sum!(10, usd),
+            "",
+        );
+        let mut other = TestBook::default();
+        let other_wallet_key = other.insert_account("wallet");
+        let other_bank_key = other.insert_account("bank");
+        other.add_account_tag(other_wallet_key, "cash");
+        other.insert_transaction(TransactionIndex(0), "b");
+        other.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            other_bank_key,
+            other_wallet_key,
+            sum!(3, usd),
+            "",
+        );
+        let report = book.merge(other);
+        assert_eq!(report.first_transaction_index(), TransactionIndex(1));
+        let wallet_key = report.account_key(other_wallet_key).unwrap();
+        let other_bank_key = report.account_key(other_bank_key).unwrap();
+        assert!(book.account_has_tag(wallet_key, "cash"));
+        assert!(!book.account_has_tag(other_bank_key, "cash"));
+        assert_eq!(
+            book.accounts().map(|(_, extra)| *extra).collect::<Vec<_>>(),
+            vec!["bank", "income", "wallet", "bank"],
+        );
+        assert_eq!(book.transactions[1].extra, "b");
+        let move_ = &book.transactions[1].moves[0];
+        assert_eq!(move_.debit_account_key, other_bank_key);
+        assert_eq!(move_.credit_account_key, wallet_key);
+        let total = book
+            .accounts()
+            .map(|(account_key, _)| {
+                *book
+                    .account_balance::<i128>(account_key)
+                    .unit_amount(&usd)
+                    .unwrap()
+            })
+            .sum::<i128>();
+        assert_eq!(total, 0);
+    }
+    #[test]
+    #[should_panic(expected = "No account found for key ")]
+    fn merge_account_not_found() {
+        let mut book = TestBook::default();
+        let mut other = TestBook::default();
+        let other_wallet_key = other.insert_account("wallet");
+        let other_bank_key = other.insert_account("bank");
+        other.insert_transaction(TransactionIndex(0), "");
+        other.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            other_bank_key,
+            other_wallet_key,
+            sum!(),
+            "",
+        );
+        other.accounts.remove(other_bank_key);
+        book.merge(other);
+    }
+    #[test]
+    fn accounts() {
+        let mut book = TestBook::default();
+        assert!(book.accounts().next().is_none());
+        let account_a_key = book.insert_account("a");
+        let account_b_key = book.insert_account("b");
+        let expected = vec![(account_a_key, &"a"), (account_b_key, &"b")];
+        let actual = book.accounts().collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+    #[test]
+    fn find_account() {
+        let mut book = Book::<&str, u64, (u8, &str), (), ()>::default();
+        book.insert_account((0, "Bank"));
+        let wallet_key = book.insert_account((1, "Wallet"));
+        book.insert_account((2, "Wallet"));
+        assert_eq!(
+            book.find_account(|(_, name)| *name == "Wallet"),
+            Some((wallet_key, &(1, "Wallet"))),
+        );
+        assert_eq!(book.find_account(|(_, name)| *name == "Income"), None);
+    }
+    #[test]
+    #[should_panic(expected = "No account found for key ")]
+    fn add_account_tag_account_not_found() {
+        let mut book = TestBook::default();
+        let account_key = book.insert_account("");
+        book.accounts.remove(account_key);
+        book.add_account_tag(account_key, "");
+    }
+    #[test]
+    fn account_tags() {
+        let mut book = TestBook::default();
+        let account_a_key = book.insert_account("a");
+        let account_b_key = book.insert_account("b");
+        let account_c_key = book.insert_account("c");
+        book.add_account_tag(account_a_key, "tax-deductible");
+        book.add_account_tag(account_c_key, "tax-deductible");
+        book.add_account_tag(account_c_key, "shared");
+        assert!(book.account_has_tag(account_a_key, "tax-deductible"));
+        assert!(!book.account_has_tag(account_b_key, "tax-deductible"));
+        assert_eq!(
+            book.accounts_with_tag("tax-deductible").collect::<Vec<_>>(),
+            vec![(account_a_key, &"a"), (account_c_key, &"c")],
+        );
+        assert!(book.remove_account_tag(account_c_key, "tax-deductible"));
+        assert!(!book.remove_account_tag(account_b_key, "tax-deductible"));
+        assert_eq!(
+            book.accounts_with_tag("tax-deductible").collect::<Vec<_>>(),
+            vec![(account_a_key, &"a")],
+        );
+        assert!(book.account_has_tag(account_c_key, "shared"));
+    }
+    #[test]
+    #[should_panic(expected = "No account found for key ")]
+    fn account_has_moves_account_not_found() {
+        let mut book = TestBook::default();
+        let account_key = book.insert_account("");
+        book.accounts.remove(account_key);
+        book.account_has_moves(account_key);
+    }
+    #[test]
+    fn account_has_moves() {
+        let mut book = TestBook::default();
+        let debit_key = book.insert_account("");
+        let credit_key = book.insert_account("");
+        let other_key = book.insert_account("");
+        book.insert_transaction(TransactionIndex(0), "");
+        assert!(!book.account_has_moves(debit_key));
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            debit_key,
+            credit_key,
+            sum!(),
+            "",
+        );
+        assert!(book.account_has_moves(debit_key));
+        assert!(book.account_has_moves(credit_key));
+        assert!(!book.account_has_moves(other_key));
+    }
+    #[test]
+    fn counts() {
+        let mut book = TestBook::default();
+        assert_eq!(book.account_count(), 0);
+        assert_eq!(book.transaction_count(), 0);
+        assert_eq!(book.move_count(), 0);
+        assert_eq!(book.unit_count(), 0);
+        assert!(book.units().is_empty());
+        let debit_key = book.insert_account("");
+        let credit_key = book.insert_account("");
+        let usd = "USD";
+        let thb = "THB";
+        append_moves(
+            &mut book,
+            &[
+                (0, debit_key, credit_key, sum!(1, usd; 2, thb), ""),
+                (1, debit_key, credit_key, sum!(3, usd), ""),
+                (1, debit_key, credit_key, sum!(), ""),
+            ],
+        );
+        assert_eq!(book.account_count(), 2);
+        assert_eq!(book.transaction_count(), 2);
+        assert_eq!(book.move_count(), 3);
+        assert_eq!(book.unit_count(), 2);
+        assert_eq!(book.units(), [&thb, &usd].iter().copied().collect());
+    }
+    #[test]
+    fn get_account() {
+        let mut book = TestBook::default();
+        book.insert_account("");
+        let account_key = book.insert_account("!");
+        book.insert_account("");
+        let account = book.get_account(account_key);
+        assert_eq!(*account, "!");
+    }
+    type RemovalBook = (TestBook, [AccountKey; 4]);
+    fn book_for_removal() -> RemovalBook {
+        let mut book = TestBook::default();
+        let account_keys = [
+            book.insert_account("a"),
+            book.insert_account("b"),
+            book.insert_account("c"),
+            book.insert_account("d"),
+        ];
+        let [a, b, c, _] = account_keys;
+        let usd = "USD";
+        append_moves(
+            &mut book,
+            &[
+                (0, a, b, sum!(1, usd), "0"),
+                (0, c, a, sum!(2, usd), "1"),
+                (0, b, c, sum!(4, usd), "2"),
+                (1, a, c, sum!(8, usd), "3"),
+            ],
+        );
+        book.add_account_tag(a, "tag");
+        (book, account_keys)
+    }
+    #[test]
+    fn remove_account_reassign() {
+        let (mut book, [a, b, c, d]) = book_for_removal();
+        let balance_of_b = book.account_balance::<i128>(b);
+        let balance_of_c = book.account_balance::<i128>(c);
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let observer_events = Arc::clone(&events);
+        book.on_change(move |event| {
+            observer_events.lock().unwrap().push(*event);
+        });
+        let report = book.remove_account(a, Some(d));
+        assert_eq!(*report.extra(), "a");
+        assert_eq!(report.reassigned_moves(), 3);
+        assert_eq!(report.removed_moves(), 0);
+        assert_eq!(book.account_count(), 3);
+        assert!(book.accounts_with_tag("tag").next().is_none());
+        let moves = book
+            .indexed_moves()
+            .map(|(_, _, move_)| {
+                (move_.debit_account_key, move_.credit_account_key)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(moves, vec![(d, b), (c, d), (b, c), (d, c)]);
+        assert_eq!(book.account_balance::<i128>(b), balance_of_b);
+        assert_eq!(book.account_balance::<i128>(c), balance_of_c);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                Event::MoveSideSet(TransactionIndex(0), MoveIndex(1), Credit),
+                Event::MoveSideSet(TransactionIndex(0), MoveIndex(0), Debit),
+                Event::MoveSideSet(TransactionIndex(1), MoveIndex(0), Debit),
+                Event::AccountRemoved(a),
+            ],
+        );
+    }
+    #[test]
+    fn remove_account_remove_moves() {
+        let (mut book, [a, b, c, _]) = book_for_removal();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let observer_events = Arc::clone(&events);
+        book.on_change(move |event| {
+            observer_events.lock().unwrap().push(*event);
+        });
+        let report = book.remove_account(a, None);
+        assert_eq!(report.reassigned_moves(), 0);
+        assert_eq!(report.removed_moves(), 3);
+        assert_eq!(report.into_extra(), "a");
+        assert_eq!(book.account_count(), 3);
+        let extras = book
+            .indexed_moves()
+            .map(|(_, _, move_)| move_.extra)
+            .collect::<Vec<_>>();
+        assert_eq!(extras, vec!["2"]);
+        assert_eq!(book.transaction_count(), 2);
+        assert!(book.validate().is_empty());
+        let usd = "USD";
+        assert_eq!(
+            book.account_balance::<i128>(b),
+            TestBalance::default() - &sum!(4, usd),
+        );
+        assert_eq!(
+            book.account_balance::<i128>(c),
+            TestBalance::default() + &sum!(4, usd),
+        );
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                Event::MoveRemoved(TransactionIndex(0), MoveIndex(1)),
+                Event::MoveRemoved(TransactionIndex(0), MoveIndex(0)),
+                Event::MoveRemoved(TransactionIndex(1), MoveIndex(0)),
+                Event::AccountRemoved(a),
+            ],
+        );
+    }
+    #[test]
+    #[should_panic(expected = "No account found for key ")]
+    fn remove_account_not_found() {
+        let (mut book, [a, _, _, _]) = book_for_removal();
+        book.remove_account(a, None);
+        book.remove_account(a, None);
+    }
+    #[test]
+    #[should_panic(expected = "Account is reassigned to itself.")]
+    fn remove_account_reassign_to_itself() {
+        let (mut book, [a, _, _, _]) = book_for_removal();
+        book.remove_account(a, Some(a));
+    }
+    #[test]
+    #[should_panic(
+        expected = "Some move is between the account and the one it is \
+                    reassigned to."
+    )]
+    fn remove_account_reassign_to_other_side() {
+        let (mut book, [a, _, c, _]) = book_for_removal();
+        book.remove_account(a, Some(c));
+    }
+    #[test]
+    fn clear() {
+        let mut book = TestBook::default();
+        let debit_key = book.insert_account("");
+        let credit_key = book.insert_account("");
+        book.add_account_tag(debit_key, "");
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            debit_key,
+            credit_key,
+            sum!(),
+            "",
+        );
+        book.clear();
+        assert_eq!(book.account_count(), 0);
+        assert_eq!(book.transaction_count(), 0);
+        assert_eq!(book.move_count(), 0);
+        assert!(book.account_tags.is_empty());
+        let account_key = book.insert_account("");
+        assert_ne!(account_key, debit_key);
+        assert_ne!(account_key, credit_key);
+    }
+    #[test]
+    fn validate() {
+        let mut book = TestBook::default();
+        let account_a_key = book.insert_account("");
+        let account_b_key = book.insert_account("");
+        let account_c_key = book.insert_account("");
+        book.insert_transaction(TransactionIndex(0), "");
+        [
+            (account_a_key, account_b_key),
+            (account_b_key, account_c_key),
+        ]
+        .iter()
+        .enumerate()
+        .for_each(|(move_index, (debit_key, credit_key))| {
+            book.insert_move(
+                TransactionIndex(0),
+                MoveIndex(move_index),
+                *debit_key,
+                *credit_key,
+                sum!(),
+                "",
+            );
+        });
+        book.add_account_tag(account_b_key, "tag");
+        assert_eq!(book.validate(), vec![]);
+        book.accounts.remove(account_b_key);
+        book.transactions[0].moves[1].credit_account_key = account_b_key;
+        assert_eq!(
+            book.validate(),
+            vec![
+                Violation::MissingAccount {
+                    transaction_index: TransactionIndex(0),
+                    move_index: MoveIndex(0),
+                    side: Credit,
+                    account_key: account_b_key,
+                },
+                Violation::MissingAccount {
+                    transaction_index: TransactionIndex(0),
+                    move_index: MoveIndex(1),
+                    side: Debit,
+                    account_key: account_b_key,
+                },
+                Violation::MissingAccount {
+                    transaction_index: TransactionIndex(0),
+                    move_index: MoveIndex(1),
+                    side: Credit,
+                    account_key: account_b_key,
+                },
+                Violation::SameAccounts {
+                    transaction_index: TransactionIndex(0),
+                    move_index: MoveIndex(1),
+                },
+                Violation::TaggedMissingAccount {
+                    account_key: account_b_key,
+                },
+            ],
+        );
+    }
+    #[test]
+    fn contains_account() {
+        let (mut book, [a, b, _, _]) = book_for_removal();
+        book.remove_account(a, None);
+        assert!(!book.contains_account(a));
+        assert!(book.contains_account(b));
+    }
+    #[test]
+    fn contains_unit() {
+        let (mut book, [a, _, _, _]) = book_for_removal();
+        let usd = "USD";
+        assert!(book.contains_unit(&usd));
+        assert!(!book.contains_unit(&"THB"));
+        book.remove_account(a, None);
+        book.remove_transaction(TransactionIndex(0));
+        assert!(!book.contains_unit(&usd));
+    }
+    #[test]
+    #[should_panic(expected = "No account found for key ")]
+    fn assert_has_account() {
+        let mut book = TestBook::default();
+        let account_key = book.insert_account("");
+        book.accounts.remove(account_key);
+        book.assert_has_account(account_key);
+    }
+    #[test]
+    #[should_panic(expected = "No account found for key ")]
+    fn account_balance_at_transaction_account_not_found() {
+        let mut book = TestBook::default();
+        book.insert_transaction(TransactionIndex(0), "");
+        let account_key = book.insert_account("");
+        book.accounts.remove(account_key);
+        book.account_balance_at_transaction::<i128>(
+            account_key,
+            TransactionIndex(0),
+        );
+    }
+    #[test]
+    fn account_balance_at_transaction() {
+        let mut book = TestBook::default();
+        let account_a_key = book.insert_account("");
+        let account_b_key = book.insert_account("");
+        let usd = "USD";
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            account_a_key,
+            account_b_key,
+            sum!(3, usd),
+            "",
+        );
+        assert_eq!(
+            book.account_balance_at_transaction::<i128>(
+                account_a_key,
+                TransactionIndex(0)
+            ),
+            TestBalance::default() - &sum!(3, usd),
+        );
+        assert_eq!(
+            book.account_balance_at_transaction::<i128>(
+                account_b_key,
+                TransactionIndex(0)
+            ),
+            TestBalance::default() + &sum!(3, usd),
+        );
+        book.insert_transaction(TransactionIndex(1), "");
+        book.insert_move(
+            TransactionIndex(1),
+            MoveIndex(0),
+            account_a_key,
+            account_b_key,
+            sum!(4, usd),
+            "",
+        );
+        assert_eq!(
+            book.account_balance_at_transaction::<i128>(
+                account_a_key,
+                TransactionIndex(0)
+            ),
+            TestBalance::default() - &sum!(3, usd),
+        );
+        assert_eq!(
+            book.account_balance_at_transaction::<i128>(
+                account_b_key,
+                TransactionIndex(0)
+            ),
+            TestBalance::default() + &sum!(3, usd),
+        );
+        assert_eq!(
+            book.account_balance_at_transaction::<i128>(
+                account_a_key,
+                TransactionIndex(1)
+            ),
+            TestBalance::default() - &sum!(7, usd),
+        );
+        assert_eq!(
+            book.account_balance_at_transaction::<i128>(
+                account_b_key,
+                TransactionIndex(1)
+            ),
+            TestBalance::default() + &sum!(7, usd),
+        );
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            account_a_key,
+            account_b_key,
+            sum!(1, usd),
+            "",
+        );
+        assert_eq!(
+            book.account_balance_at_transaction::<i128>(
+                account_a_key,
+                TransactionIndex(0)
+            ),
+            TestBalance::default() - &sum!(1, usd),
+        );
+        assert_eq!(
+            book.account_balance_at_transaction::<i128>(
+                account_b_key,
+                TransactionIndex(0)
+            ),
+            TestBalance::default() + &sum!(1, usd),
+        );
+        assert_eq!(
+            book.account_balance_at_transaction::<i128>(
+                account_a_key,
+                TransactionIndex(1)
+            ),
+            TestBalance::default() - &sum!(4, usd),
+        );
+        assert_eq!(
+            book.account_balance_at_transaction::<i128>(
+                account_b_key,
+                TransactionIndex(1)
+            ),
+            TestBalance::default() + &sum!(4, usd),
+        );
+        assert_eq!(
+            book.account_balance_at_transaction::<i128>(
+                account_a_key,
+                TransactionIndex(2)
+            ),
+            TestBalance::default() - &sum!(8, usd),
+        );
+        assert_eq!(
+            book.account_balance_at_transaction::<i128>(
+                account_b_key,
+                TransactionIndex(2)
+            ),
+            TestBalance::default() + &sum!(8, usd),
         );
-    }
-}
-#[cfg(test)]
-mod test {
-    use super::{
-        Side::{Credit, Debit},
-        TransactionIndex,
-    };
-    use crate::{
-        test_utils::{TestBalance, TestBook},
-        transaction::MoveIndex,
-    };
-    #[test]
-    fn default() {
-        let book = TestBook::default();
-        assert!(book.accounts.is_empty());
-        assert!(book.transactions.is_empty());
     }
     #[test]
-    fn insert_account() {
+    #[should_panic(
+        expected = "range end index 1 out of range for slice of length 1"
+    )]
+    fn account_balance_at_transaction_panic_out_of_bounds() {
         let mut book = TestBook::default();
-        book.insert_account("");
-        assert_eq!(book.accounts.len(), 1);
+        book.insert_transaction(TransactionIndex(0), "");
+        let account_key = book.insert_account("");
+        book.account_balance_at_transaction::<i128>(
+            account_key,
+            TransactionIndex(1),
+        );
     }
     #[test]
-    #[should_panic(expected = "insertion index (is 1) should be <= len (is 0)")]
-    fn insert_transaction_panic_index_out_of_bounds() {
+    #[should_panic(expected = "No account found for key ")]
+    fn account_balance_account_not_found() {
         let mut book = TestBook::default();
-        book.insert_transaction(TransactionIndex(1), "");
+        let account_key = book.insert_account("");
+        book.accounts.remove(account_key);
+        book.account_balance::<i128>(account_key);
     }
     #[test]
-    fn insert_transaction() {
+    fn account_balance() {
         let mut book = TestBook::default();
-        book.insert_transaction(TransactionIndex(0), "a");
-        book.insert_transaction(TransactionIndex(1), "b");
-        book.insert_transaction(TransactionIndex(0), "c");
-        book.insert_transaction(TransactionIndex(2), "d");
+        let account_a_key = book.insert_account("");
+        let account_b_key = book.insert_account("");
+        let usd = "USD";
         assert_eq!(
-            book.transactions
-                .iter()
-                .map(|transaction| transaction.extra())
-                .collect::<Vec<_>>(),
-            [&"c", &"a", &"d", &"b"],
+            book.account_balance::<i128>(account_a_key),
+            TestBalance::default(),
         );
-    }
-    #[test]
-    #[should_panic(expected = "insertion index (is 1) should be <= len (is 0)")]
-    fn insert_move_panic_index_out_of_bounds() {
-        let mut book = TestBook::default();
-        let debit_key = book.insert_account("");
-        let credit_key = book.insert_account("");
         book.insert_transaction(TransactionIndex(0), "");
         book.insert_move(
             TransactionIndex(0),
-            MoveIndex(1),
-            debit_key,
-            credit_key,
-            sum!(),
+            MoveIndex(0),
+            account_a_key,
+            account_b_key,
+            sum!(3, usd),
             "",
         );
-    }
-    #[test]
-    #[should_panic(expected = "No account found for key ")]
-    fn insert_move_panic_debit_account_not_found() {
-        let mut book = TestBook::default();
-        let debit_key = book.insert_account("");
-        book.accounts.remove(debit_key);
-        let credit_key = book.insert_account("");
-        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_transaction(TransactionIndex(1), "");
         book.insert_move(
-            TransactionIndex(0),
+            TransactionIndex(1),
             MoveIndex(0),
-            debit_key,
-            credit_key,
-            sum!(),
+            account_a_key,
+            account_b_key,
+            sum!(4, usd),
             "",
         );
+        assert_eq!(
+            book.account_balance::<i128>(account_a_key),
+            TestBalance::default() - &sum!(7, usd),
+        );
+        assert_eq!(
+            book.account_balance::<i128>(account_b_key),
+            TestBalance::default() + &sum!(7, usd),
+        );
     }
     #[test]
-    #[should_panic(expected = "No account found for key ")]
-    fn insert_move_panic_credit_account_not_found() {
+    fn account_balances() {
         let mut book = TestBook::default();
-        let debit_key = book.insert_account("");
-        let credit_key = book.insert_account("");
-        book.accounts.remove(credit_key);
-        book.insert_transaction(TransactionIndex(0), "");
-        book.insert_move(
-            TransactionIndex(0),
-            MoveIndex(0),
-            debit_key,
-            credit_key,
-            sum!(),
-            "",
+        let account_keys = [
+            book.insert_account(""),
+            book.insert_account(""),
+            book.insert_account(""),
+            book.insert_account(""),
+        ];
+        let usd = "USD";
+        let thb = "THB";
+        let [a, b, c, _] = account_keys;
+        append_moves(
+            &mut book,
+            &[
+                (0, a, b, sum!(2, usd), ""),
+                (1, b, c, sum!(4, thb), ""),
+                (1, c, a, sum!(1, usd; 1, thb), ""),
+            ],
         );
+        let balances = book.account_balances::<i128>();
+        assert_eq!(balances.len(), 4);
+        account_keys.iter().for_each(|account_key| {
+            assert_eq!(
+                balances[account_key],
+                book.account_balance::<i128>(*account_key),
+            );
+        });
+        assert_eq!(balances[&account_keys[3]], TestBalance::default());
     }
     #[test]
-    fn insert_move() {
+    fn combined_balance() {
         let mut book = TestBook::default();
-        book.insert_transaction(TransactionIndex(0), "");
-        let debit_key = book.insert_account("");
-        let credit_key = book.insert_account("");
-        book.insert_move(
-            TransactionIndex(0),
-            MoveIndex(0),
-            debit_key,
-            credit_key,
-            sum!(),
-            "a",
-        );
-        book.insert_move(
-            TransactionIndex(0),
-            MoveIndex(0),
-            debit_key,
-            credit_key,
-            sum!(),
-            "b",
+        let account_a_key = book.insert_account("");
+        let account_b_key = book.insert_account("");
+        let account_c_key = book.insert_account("");
+        let usd = "USD";
+        let thb = "THB";
+        append_moves(
+            &mut book,
+            &[
+                (0, account_a_key, account_b_key, sum!(3, usd), ""),
+                (0, account_c_key, account_a_key, sum!(5, usd; 2, thb), ""),
+                (0, account_b_key, account_c_key, sum!(1, thb), ""),
+            ],
         );
-        book.insert_move(
-            TransactionIndex(0),
-            MoveIndex(1),
-            debit_key,
-            credit_key,
-            sum!(),
-            "c",
+        let mut expected = book.account_balance::<i128>(account_a_key);
+        book.account_balance::<i128>(account_b_key)
+            .amounts()
+            .for_each(|(unit, amount)| expected += &(*unit, *amount));
+        assert_eq!(
+            book.combined_balance::<i128>(&[account_a_key, account_b_key]),
+            expected,
         );
-        book.insert_move(
-            TransactionIndex(0),
-            MoveIndex(2),
-            debit_key,
-            credit_key,
-            sum!(),
-            "d",
+        assert_eq!(
+            book.combined_balance::<i128>(&[account_a_key, account_b_key]),
+            TestBalance::default() + &sum!(5, usd; 1, thb),
         );
         assert_eq!(
-            book.transactions[0]
-                .moves
-                .iter()
-                .map(|move_| move_.extra)
-                .collect::<Vec<_>>(),
-            vec!["b", "c", "d", "a"],
+            book.combined_balance::<i128>(&[
+                account_a_key,
+                account_b_key,
+                account_c_key,
+            ]),
+            TestBalance::default() + &sum!(0, usd; 0, thb),
         );
+        assert_eq!(book.combined_balance::<i128>(&[]), TestBalance::default());
     }
     #[test]
-    fn accounts() {
+    #[should_panic(expected = "No account found for key ")]
+    fn combined_balance_account_not_found() {
         let mut book = TestBook::default();
-        assert!(book.accounts().next().is_none());
-        let account_a_key = book.insert_account("a");
-        let account_b_key = book.insert_account("b");
-        let expected = vec![(account_a_key, &"a"), (account_b_key, &"b")];
-        let actual = book.accounts().collect::<Vec<_>>();
-        assert_eq!(actual, expected);
+        let account_a_key = book.insert_account("");
+        let account_b_key = book.insert_account("");
+        book.accounts.remove(account_b_key);
+        book.combined_balance::<i128>(&[account_a_key, account_b_key]);
     }
     #[test]
-    fn get_account() {
+    #[should_panic(expected = "No account found for key ")]
+    fn account_unit_balance_account_not_found() {
         let mut book = TestBook::default();
-        book.insert_account("");
-        let account_key = book.insert_account("!");
-        book.insert_account("");
-        let account = book.get_account(account_key);
-        assert_eq!(*account, "!");
+        let account_key = book.insert_account("");
+        book.clear();
+        book.account_unit_balance::<i128>(account_key, &"USD");
     }
     #[test]
-    #[should_panic(expected = "No account found for key ")]
-    fn assert_has_account() {
+    fn account_unit_balance() {
         let mut book = TestBook::default();
-        let account_key = book.insert_account("");
-        book.accounts.remove(account_key);
-        book.assert_has_account(account_key);
+        let account_a_key = book.insert_account("");
+        let account_b_key = book.insert_account("");
+        let account_c_key = book.insert_account("");
+        let usd = "USD";
+        let thb = "THB";
+        append_moves(
+            &mut book,
+            &[
+                (0, account_a_key, account_b_key, sum!(3, usd; 10, thb), ""),
+                (0, account_b_key, account_a_key, sum!(1, usd), ""),
+                (0, account_c_key, account_b_key, sum!(7, usd), ""),
+                (0, account_c_key, account_a_key, sum!(4, thb), ""),
+            ],
+        );
+        let balance = book.account_balance::<i128>(account_a_key);
+        [usd, thb].iter().for_each(|unit| {
+            assert_eq!(
+                book.account_unit_balance::<i128>(account_a_key, unit),
+                *balance.unit_amount(unit).unwrap(),
+            );
+        });
+        assert_eq!(book.account_unit_balance::<i128>(account_a_key, &usd), -2);
+        assert_eq!(book.account_unit_balance::<i128>(account_a_key, &thb), -6);
+        assert_eq!(book.account_unit_balance::<i128>(account_b_key, &usd), 9);
+        let eur = "EUR";
+        assert_eq!(book.account_unit_balance::<i128>(account_a_key, &eur), 0);
     }
     #[test]
     #[should_panic(expected = "No account found for key ")]
-    fn account_balance_at_transaction_account_not_found() {
+    fn account_statement_account_not_found() {
         let mut book = TestBook::default();
-        book.insert_transaction(TransactionIndex(0), "");
         let account_key = book.insert_account("");
         book.accounts.remove(account_key);
-        book.account_balance_at_transaction::<i128>(
-            account_key,
-            TransactionIndex(0),
-        );
+        book.account_statement::<i128>(account_key).next();
     }
     #[test]
-    fn account_balance_at_transaction() {
+    fn account_statement() {
         let mut book = TestBook::default();
         let account_a_key = book.insert_account("");
         let account_b_key = book.insert_account("");
+        let account_c_key = book.insert_account("");
         let usd = "USD";
         book.insert_transaction(TransactionIndex(0), "");
         book.insert_move(
             TransactionIndex(0),
             MoveIndex(0),
+            account_b_key,
             account_a_key,
+            sum!(10, usd),
+            "a",
+        );
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(1),
             account_b_key,
-            sum!(3, usd),
+            account_c_key,
+            sum!(5, usd),
             "",
         );
-        assert_eq!(
-            book.account_balance_at_transaction::<i128>(
-                account_a_key,
-                TransactionIndex(0)
-            ),
-            TestBalance::default() - &sum!(3, usd),
-        );
-        assert_eq!(
-            book.account_balance_at_transaction::<i128>(
-                account_b_key,
-                TransactionIndex(0)
-            ),
-            TestBalance::default() + &sum!(3, usd),
+        book.insert_transaction(TransactionIndex(1), "");
+        book.insert_move(
+            TransactionIndex(1),
+            MoveIndex(0),
+            account_a_key,
+            account_c_key,
+            sum!(3, usd),
+            "b",
         );
-        book.insert_transaction(TransactionIndex(1), "");
         book.insert_move(
             TransactionIndex(1),
-            MoveIndex(0),
+            MoveIndex(1),
+            account_c_key,
             account_a_key,
-            account_b_key,
-            sum!(4, usd),
-            "",
+            sum!(1, usd),
+            "c",
         );
-        assert_eq!(
-            book.account_balance_at_transaction::<i128>(
-                account_a_key,
-                TransactionIndex(0)
-            ),
-            TestBalance::default() - &sum!(3, usd),
+        let actual = book
+            .account_statement::<i128>(account_a_key)
+            .map(|(transaction_index, move_index, move_, balance)| {
+                (transaction_index.0, move_index.0, move_.extra, balance)
+            })
+            .collect::<Vec<_>>();
+        let expected = vec![
+            (0, 0, "a", TestBalance::default() + &sum!(10, usd)),
+            (1, 0, "b", TestBalance::default() + &sum!(7, usd)),
+            (1, 1, "c", TestBalance::default() + &sum!(8, usd)),
+        ];
+        assert_eq!(actual, expected);
+    }
+    #[test]
+    fn unit_moves() {
+        let mut book = TestBook::default();
+        let debit_key = book.insert_account("");
+        let credit_key = book.insert_account("");
+        let usd = "USD";
+        let thb = "THB";
+        append_moves(
+            &mut book,
+            &[
+                (0, debit_key, credit_key, sum!(1, usd), "a"),
+                (0, debit_key, credit_key, sum!(1, thb), "b"),
+                (1, debit_key, credit_key, sum!(1, usd; 1, thb), "c"),
+            ],
         );
-        assert_eq!(
-            book.account_balance_at_transaction::<i128>(
-                account_b_key,
-                TransactionIndex(0)
-            ),
-            TestBalance::default() + &sum!(3, usd),
+        let actual = book
+            .unit_moves(&usd)
+            .map(|(transaction_index, move_index, move_)| {
+                (transaction_index, move_index, move_.extra)
+            })
+            .collect::<Vec<_>>();
+        let expected = vec![
+            (TransactionIndex(0), MoveIndex(0), "a"),
+            (TransactionIndex(1), MoveIndex(0), "c"),
+        ];
+        assert_eq!(actual, expected);
+        assert!(book.unit_moves(&"ILS").next().is_none());
+    }
+    #[test]
+    fn insert_opening_balance() {
+        let mut book = TestBook::default();
+        let account_key = book.insert_account("");
+        let equity_key = book.insert_account("");
+        let usd = "USD";
+        let thb = "THB";
+        let eur = "EUR";
+        book.insert_transaction(TransactionIndex(0), "later");
+        let opening_balance =
+            TestBalance::default() + &sum!(5, usd; 0, eur) - &sum!(3, thb);
+        book.insert_opening_balance(
+            account_key,
+            equity_key,
+            &opening_balance,
+            "opening",
+            "",
         );
+        assert_eq!(book.transactions[0].extra, "opening");
+        assert_eq!(book.transactions[1].extra, "later");
+        assert_eq!(book.transactions[0].moves.len(), 2);
         assert_eq!(
-            book.account_balance_at_transaction::<i128>(
-                account_a_key,
-                TransactionIndex(1)
-            ),
-            TestBalance::default() - &sum!(7, usd),
+            book.account_balance::<i128>(account_key),
+            TestBalance::default() + &sum!(5, usd) - &sum!(3, thb),
         );
         assert_eq!(
-            book.account_balance_at_transaction::<i128>(
-                account_b_key,
-                TransactionIndex(1)
-            ),
-            TestBalance::default() + &sum!(7, usd),
+            book.account_balance::<i128>(equity_key),
+            TestBalance::default() - &sum!(5, usd) + &sum!(3, thb),
+        );
+    }
+    #[test]
+    fn insert_opening_balance_positive_only() {
+        let mut book = TestBook::default();
+        let account_key = book.insert_account("");
+        let equity_key = book.insert_account("");
+        let usd = "USD";
+        let opening_balance = TestBalance::default() + &sum!(5, usd);
+        book.insert_opening_balance(
+            account_key,
+            equity_key,
+            &opening_balance,
+            "",
+            "",
         );
+        assert_eq!(book.transactions[0].moves.len(), 1);
+        assert_eq!(book.account_balance::<i128>(account_key), opening_balance);
+    }
+    #[test]
+    fn close_account() {
+        let mut book = TestBook::default();
+        let account_key = book.insert_account("");
+        let equity_key = book.insert_account("");
+        let other_key = book.insert_account("");
+        let usd = "USD";
+        let thb = "THB";
         book.insert_transaction(TransactionIndex(0), "");
         book.insert_move(
             TransactionIndex(0),
             MoveIndex(0),
-            account_a_key,
-            account_b_key,
-            sum!(1, usd),
+            other_key,
+            account_key,
+            sum!(5, usd; 2, thb),
             "",
         );
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(1),
+            account_key,
+            other_key,
+            sum!(7, thb),
+            "",
+        );
+        let transaction_index =
+            book.close_account::<i128>(account_key, equity_key, "closing", "");
+        assert_eq!(transaction_index, TransactionIndex(1));
+        assert_eq!(book.transactions[1].extra, "closing");
+        assert_eq!(book.transactions[1].moves.len(), 2);
         assert_eq!(
-            book.account_balance_at_transaction::<i128>(
-                account_a_key,
-                TransactionIndex(0)
-            ),
-            TestBalance::default() - &sum!(1, usd),
+            book.account_balance::<i128>(account_key),
+            TestBalance::default() + &sum!(0, usd; 0, thb),
         );
         assert_eq!(
-            book.account_balance_at_transaction::<i128>(
-                account_b_key,
-                TransactionIndex(0)
-            ),
-            TestBalance::default() + &sum!(1, usd),
+            book.account_balance::<i128>(equity_key),
+            TestBalance::default() + &sum!(5, usd) - &sum!(5, thb),
         );
+    }
+    #[test]
+    fn moves_between_flow_between_and_flow_graph() {
+        let mut book = TestBook::default();
+        let account_a_key = book.insert_account("");
+        let account_b_key = book.insert_account("");
+        let account_c_key = book.insert_account("");
+        let usd = "USD";
+        let thb = "THB";
+        append_moves(
+            &mut book,
+            &[
+                (0, account_a_key, account_b_key, sum!(1, usd), "a"),
+                (0, account_b_key, account_a_key, sum!(2, usd), "b"),
+                (0, account_a_key, account_c_key, sum!(4, usd), "c"),
+                (1, account_a_key, account_b_key, sum!(8, usd; 3, thb), "d"),
+            ],
+        );
+        let extras = |debit_key, credit_key| {
+            book.moves_between(debit_key, credit_key)
+                .map(|(_, _, move_)| move_.extra)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(extras(account_a_key, account_b_key), vec!["a", "d"]);
+        assert_eq!(extras(account_b_key, account_a_key), vec!["b"]);
+        assert!(extras(account_c_key, account_a_key).is_empty());
         assert_eq!(
-            book.account_balance_at_transaction::<i128>(
-                account_a_key,
-                TransactionIndex(1)
-            ),
-            TestBalance::default() - &sum!(4, usd),
+            book.flow_between::<i128>(account_a_key, account_b_key),
+            TestBalance::default() + &sum!(9, usd; 3, thb),
         );
         assert_eq!(
-            book.account_balance_at_transaction::<i128>(
-                account_b_key,
-                TransactionIndex(1)
-            ),
-            TestBalance::default() + &sum!(4, usd),
+            book.flow_between::<i128>(account_b_key, account_a_key),
+            TestBalance::default() + &sum!(2, usd),
         );
         assert_eq!(
-            book.account_balance_at_transaction::<i128>(
-                account_a_key,
-                TransactionIndex(2)
-            ),
-            TestBalance::default() - &sum!(8, usd),
+            book.flow_between::<i128>(account_c_key, account_a_key),
+            TestBalance::default(),
         );
         assert_eq!(
-            book.account_balance_at_transaction::<i128>(
-                account_b_key,
-                TransactionIndex(2)
-            ),
-            TestBalance::default() + &sum!(8, usd),
+            book.flow_graph::<i128>(),
+            btreemap! {
+                (account_a_key, account_b_key) =>
+                    TestBalance::default() + &sum!(9, usd; 3, thb),
+                (account_b_key, account_a_key) =>
+                    TestBalance::default() + &sum!(2, usd),
+                (account_a_key, account_c_key) =>
+                    TestBalance::default() + &sum!(4, usd),
+            },
         );
     }
     #[test]
     #[should_panic(expected = "No account found for key ")]
+    fn moves_between_account_not_found() {
+        let mut book = TestBook::default();
+        let account_a_key = book.insert_account("");
+        let account_b_key = book.insert_account("");
+        book.accounts.remove(account_b_key);
+        book.moves_between(account_a_key, account_b_key).next();
+    }
+    #[test]
+    #[should_panic(expected = "No account found for key ")]
     fn set_account_panic() {
         let mut book = TestBook::default();
         let account_key = book.insert_account("");
@@ -863,6 +3748,76 @@ mod test {
         book.insert_transaction(TransactionIndex(0), "");
         book.set_move_sum(TransactionIndex(0), MoveIndex(0), sum!());
     }
+    fn book_with_duplicates() -> (TestBook, AccountKey) {
+        let mut book = TestBook::default();
+        let a = book.insert_account("");
+        let b = book.insert_account("");
+        let usd = "USD";
+        append_moves(
+            &mut book,
+            &[
+                (0, a, b, sum!(5, usd), "0"),
+                (0, b, a, sum!(5, usd), "1"),
+                (0, a, b, sum!(5, usd), "2"),
+                (1, a, b, sum!(5, usd), "3"),
+                (1, a, b, sum!(7, usd), "4"),
+            ],
+        );
+        (book, a)
+    }
+    fn move_extras(book: &TestBook) -> Vec<&'static str> {
+        book.indexed_moves()
+            .map(|(_, _, move_)| move_.extra)
+            .collect()
+    }
+    #[test]
+    fn dedup_moves() {
+        let (mut book, _) = book_with_duplicates();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let observer_events = Arc::clone(&events);
+        book.on_change(move |event| {
+            observer_events.lock().unwrap().push(*event);
+        });
+        let removed = book.dedup_moves();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].extra, "2");
+        assert_eq!(move_extras(&book), vec!["0", "1", "3", "4"]);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![Event::MoveRemoved(TransactionIndex(0), MoveIndex(2))],
+        );
+    }
+    #[test]
+    fn dedup_moves_by_key() {
+        let (mut book, a) = book_with_duplicates();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let observer_events = Arc::clone(&events);
+        book.on_change(move |event| {
+            observer_events.lock().unwrap().push(*event);
+        });
+        let removed = book
+            .dedup_moves_by_key(|_, _| 0)
+            .into_iter()
+            .map(|move_| move_.extra)
+            .collect::<Vec<_>>();
+        assert_eq!(removed, vec!["2", "3"]);
+        assert_eq!(move_extras(&book), vec!["0", "1", "4"]);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                Event::MoveRemoved(TransactionIndex(1), MoveIndex(0)),
+                Event::MoveRemoved(TransactionIndex(0), MoveIndex(2)),
+            ],
+        );
+        let usd = "USD";
+        assert_eq!(
+            book.account_balance::<i128>(a),
+            TestBalance::default() - &sum!(7, usd),
+        );
+        let (mut book, _) = book_with_duplicates();
+        let removed = book.dedup_moves_by_key(|_, move_| move_.extra);
+        assert!(removed.is_empty());
+    }
     #[test]
     fn set_move_sum() {
         let mut book = TestBook::default();
@@ -884,4 +3839,417 @@ mod test {
             &100,
         );
     }
+    #[test]
+    fn rename_unit() {
+        let mut book = TestBook::default();
+        let debit_key = book.insert_account("");
+        let credit_key = book.insert_account("");
+        let usd = "USD";
+        let thb = "THB";
+        append_moves(
+            &mut book,
+            &[
+                (0, debit_key, credit_key, sum!(1, usd; 2, thb), ""),
+                (1, debit_key, credit_key, sum!(4, usd), ""),
+                (1, debit_key, credit_key, sum!(3, thb), ""),
+            ],
+        );
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let observer_events = Arc::clone(&events);
+        book.on_change(move |event| {
+            observer_events.lock().unwrap().push(*event);
+        });
+        let us_dollar = "US$";
+        book.rename_unit(&usd, us_dollar);
+        let sums = book
+            .indexed_moves()
+            .map(|(_, _, move_)| move_.sum.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sums,
+            vec![sum!(1, us_dollar; 2, thb), sum!(4, us_dollar), sum!(3, thb)],
+        );
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                Event::MoveSumSet(TransactionIndex(0), MoveIndex(0)),
+                Event::MoveSumSet(TransactionIndex(1), MoveIndex(0)),
+            ],
+        );
+    }
+    #[test]
+    #[should_panic(expected = "Unit is already in the book.")]
+    fn rename_unit_already_in_book() {
+        let mut book = TestBook::default();
+        let debit_account_key = book.insert_account("");
+        let credit_account_key = book.insert_account("");
+        let usd = "USD";
+        let thb = "THB";
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            debit_account_key,
+            credit_account_key,
+            sum!(1, usd; 2, thb),
+            "",
+        );
+        book.rename_unit(&usd, thb);
+    }
+    #[test]
+    fn rename_unit_to_itself_or_unknown_unit() {
+        let mut book = TestBook::default();
+        let debit_key = book.insert_account("");
+        let credit_key = book.insert_account("");
+        let usd = "USD";
+        let thb = "THB";
+        append_moves(
+            &mut book,
+            &[(0, debit_key, credit_key, sum!(1, usd; 2, thb), "")],
+        );
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let observer_events = Arc::clone(&events);
+        book.on_change(move |event| {
+            observer_events.lock().unwrap().push(*event);
+        });
+        book.rename_unit(&usd, usd);
+        book.rename_unit(&"ILS", "EUR");
+        let sums = book
+            .indexed_moves()
+            .map(|(_, _, move_)| move_.sum.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(sums, vec![sum!(1, usd; 2, thb)]);
+        assert!(events.lock().unwrap().is_empty());
+    }
+    #[test]
+    #[should_panic(expected = "Start transaction is after end transaction.")]
+    fn income_statement_panic_start_after_end() {
+        let mut book = TestBook::default();
+        book.insert_transaction(TransactionIndex(0), "");
+        book.insert_transaction(TransactionIndex(1), "");
+        book.income_statement::<i128>(
+            TransactionIndex(1),
+            TransactionIndex(0),
+            |_, _| false,
+            |_, _| false,
+        );
+    }
+    #[test]
+    fn income_statement() {
+        let mut book = TestBook::default();
+        let salary = book.insert_account("income");
+        let bank = book.insert_account("");
+        let groceries = book.insert_account("expense");
+        let usd = "USD";
+        [
+            (salary, bank, 100),
+            (bank, groceries, 30),
+            (salary, bank, 50),
+            (bank, groceries, 10),
+        ]
+        .iter()
+        .enumerate()
+        .for_each(|(index, (debit, credit, amount))| {
+            book.insert_transaction(TransactionIndex(index), "");
+            book.insert_move(
+                TransactionIndex(index),
+                MoveIndex(0),
+                *debit,
+                *credit,
+                sum!(*amount, usd),
+                "",
+            );
+        });
+        let statement = book.income_statement::<i128>(
+            TransactionIndex(1),
+            TransactionIndex(2),
+            |_, extra| *extra == "income",
+            |_, extra| *extra == "expense",
+        );
+        assert_eq!(
+            statement.income(),
+            &(TestBalance::default() + &sum!(50, usd)),
+        );
+        assert_eq!(
+            statement.expenses(),
+            &(TestBalance::default() + &sum!(30, usd)),
+        );
+        assert_eq!(statement.net(), &(TestBalance::default() + &sum!(20, usd)));
+    }
+    #[test]
+    fn on_change() {
+        let mut book = TestBook::default();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let observer_events = Arc::clone(&events);
+        book.on_change(move |event| {
+            observer_events.lock().unwrap().push(*event);
+        });
+        let account_key = book.insert_account("");
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![Event::AccountInserted(account_key)],
+        );
+        let other_account_key = book.insert_account("");
+        book.set_account(account_key, "!");
+        book.insert_transaction(TransactionIndex(0), "");
+        book.set_transaction_extra(TransactionIndex(0), "!");
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            account_key,
+            other_account_key,
+            sum!(),
+            "",
+        );
+        book.set_move_extra(TransactionIndex(0), MoveIndex(0), "!");
+        book.set_move_sum(TransactionIndex(0), MoveIndex(0), sum!());
+        book.set_move_side(
+            TransactionIndex(0),
+            MoveIndex(0),
+            Credit,
+            other_account_key,
+        );
+        book.remove_move(TransactionIndex(0), MoveIndex(0));
+        book.remove_transaction(TransactionIndex(0));
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                Event::AccountInserted(account_key),
+                Event::AccountInserted(other_account_key),
+                Event::AccountSet(account_key),
+                Event::TransactionInserted(TransactionIndex(0)),
+                Event::TransactionExtraSet(TransactionIndex(0)),
+                Event::MoveInserted(TransactionIndex(0), MoveIndex(0)),
+                Event::MoveExtraSet(TransactionIndex(0), MoveIndex(0)),
+                Event::MoveSumSet(TransactionIndex(0), MoveIndex(0)),
+                Event::MoveSideSet(TransactionIndex(0), MoveIndex(0), Credit,),
+                Event::MoveRemoved(TransactionIndex(0), MoveIndex(0)),
+                Event::TransactionRemoved(TransactionIndex(0)),
+            ],
+        );
+    }
+    proptest! {
+        #[test]
+        fn account_balances_add_up_to_zero(
+            moves in vec(
+                (0..3_usize, 1..3_usize, 0..2_usize, 0..1000_u64),
+                0..32,
+            ),
+        ) {
+            let mut book = TestBook::default();
+            let account_keys = [
+                book.insert_account(""),
+                book.insert_account(""),
+                book.insert_account(""),
+            ];
+            let units = ["USD", "THB"];
+            moves.iter().enumerate().for_each(
+                |(index, (debit, credit_offset, unit, amount))| {
+                    let unit = units[*unit];
+                    book.insert_transaction(TransactionIndex(index), "");
+                    book.insert_move(
+                        TransactionIndex(index),
+                        MoveIndex(0),
+                        account_keys[*debit],
+                        account_keys[(debit + credit_offset) % 3],
+                        sum!(*amount, unit),
+                        "",
+                    );
+                },
+            );
+            let total = account_keys.iter().fold(
+                TestBalance::default(),
+                |total, account_key| {
+                    book.account_balance::<i128>(*account_key)
+                        .amounts()
+                        .fold(total, |total, (unit, amount)| {
+                            total + &(*unit, *amount)
+                        })
+                },
+            );
+            assert!(total.amounts().all(|(_, amount)| *amount == 0));
+        }
+    }
+    #[test]
+    #[cfg(feature = "bincode")]
+    fn to_bytes_from_bytes() {
+        type OwnedBook = Book<String, u64, String, String, String>;
+        let mut book = OwnedBook::default();
+        let account_a_key = book.insert_account("a".to_owned());
+        let account_b_key = book.insert_account("b".to_owned());
+        let account_c_key = book.insert_account("c".to_owned());
+        book.set_account(account_a_key, "a".to_owned());
+        book.accounts.remove(account_b_key);
+        book.add_account_tag(account_a_key, "tag");
+        book.insert_transaction(TransactionIndex(0), "t".to_owned());
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            account_a_key,
+            account_c_key,
+            crate::sum::Sum::of("USD".to_owned(), 5),
+            "m".to_owned(),
+        );
+        let mut decoded = OwnedBook::from_bytes(&book.to_bytes()).unwrap();
+        assert_eq!(
+            decoded.accounts().collect::<Vec<_>>(),
+            book.accounts().collect::<Vec<_>>(),
+        );
+        assert!(decoded.account_has_tag(account_a_key, "tag"));
+        assert_eq!(decoded.transaction_count(), 1);
+        let transaction = &decoded.transactions[0];
+        assert_eq!(transaction.extra, "t");
+        let move_ = &transaction.moves[0];
+        assert_eq!(move_.debit_account_key, account_a_key);
+        assert_eq!(move_.credit_account_key, account_c_key);
+        assert_eq!(move_.sum, book.transactions[0].moves[0].sum);
+        assert_eq!(move_.extra, "m");
+        assert_ne!(decoded.insert_account("d".to_owned()), account_b_key);
+    }
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip_keeps_order() {
+        type OwnedBook = Book<String, u64, String, String, String>;
+        let mut book = OwnedBook::default();
+        let account_keys = ["a", "b", "c"]
+            .iter()
+            .map(|name| book.insert_account((*name).to_owned()))
+            .collect::<Vec<_>>();
+        (0..3).for_each(|transaction_index| {
+            book.insert_transaction(
+                TransactionIndex(transaction_index),
+                transaction_index.to_string(),
+            );
+            (0..4).for_each(|move_index| {
+                book.insert_move(
+                    TransactionIndex(transaction_index),
+                    MoveIndex(move_index),
+                    account_keys[(transaction_index + move_index) % 3],
+                    account_keys[(transaction_index + move_index + 1) % 3],
+                    crate::sum::Sum::of("USD".to_owned(), move_index as u64),
+                    format!("{}.{}", transaction_index, move_index),
+                );
+            });
+        });
+        let json = serde_json::to_string(&book).unwrap();
+        let decoded: OwnedBook = serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", book));
+        let extras = |book: &OwnedBook| {
+            book.query()
+                .moves()
+                .map(|(_, _, move_)| move_.extra.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(extras(&decoded), extras(&book));
+    }
+    #[test]
+    #[cfg(feature = "bincode")]
+    fn from_bytes_error() {
+        let error =
+            Book::<String, u64, String, String, String>::from_bytes(&[1, 2, 3])
+                .err()
+                .unwrap();
+        assert!(error.to_string().starts_with("failed to decode book: "));
+    }
+    #[test]
+    #[cfg(feature = "bincode")]
+    fn from_bytes_invalid() {
+        use super::DecodeError;
+        type OwnedBook = Book<String, u64, String, String, String>;
+        let mut book = OwnedBook::default();
+        let account_a_key = book.insert_account("a".to_owned());
+        let account_b_key = book.insert_account("b".to_owned());
+        book.insert_transaction(TransactionIndex(0), "".to_owned());
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            account_a_key,
+            account_b_key,
+            crate::sum::Sum::of("USD".to_owned(), 5),
+            "".to_owned(),
+        );
+        book.add_account_tag(account_b_key, "tag");
+        book.accounts.remove(account_b_key);
+        let error = OwnedBook::from_bytes(&book.to_bytes()).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "decoded book is invalid: 2 problems found",
+        );
+        match error {
+            DecodeError::Invalid(violations) => assert_eq!(
+                violations,
+                vec![
+                    Violation::MissingAccount {
+                        transaction_index: TransactionIndex(0),
+                        move_index: MoveIndex(0),
+                        side: Credit,
+                        account_key: account_b_key,
+                    },
+                    Violation::TaggedMissingAccount {
+                        account_key: account_b_key,
+                    },
+                ],
+            ),
+            _ => panic!("unexpected error: {:?}", error),
+        }
+    }
+    #[test]
+    #[cfg(feature = "bincode")]
+    fn from_bytes_hand_edited_duplicate_key() {
+        use super::DecodeError;
+        type OwnedBook = Book<String, u64, String, String, String>;
+        let mut book = OwnedBook::default();
+        let account_a_key = book.insert_account("a".to_owned());
+        let account_b_key = book.insert_account("b".to_owned());
+        book.insert_transaction(TransactionIndex(0), "".to_owned());
+        book.insert_move(
+            TransactionIndex(0),
+            MoveIndex(0),
+            account_a_key,
+            account_b_key,
+            crate::sum::Sum::of("USD".to_owned(), 5),
+            "".to_owned(),
+        );
+        let bytes = book.to_bytes();
+        let replace = |bytes: &[u8], from: &[u8], to: &[u8]| {
+            let position = bytes
+                .windows(from.len())
+                .position(|window| window == from)
+                .unwrap();
+            [&bytes[..position], to, &bytes[position + from.len()..]].concat()
+        };
+        // Accounts are encoded by their slots, without keys, so a copy of the
+        // slot of account `a` is a distinct account.
+        let slot = |extra: &str| {
+            let value = bincode::serialize(&Some(extra)).unwrap();
+            [value, bincode::serialize(&1u32).unwrap()].concat()
+        };
+        let edited = replace(&bytes, &slot("b"), &slot("a"));
+        let decoded = OwnedBook::from_bytes(&edited).unwrap();
+        assert_eq!(
+            decoded.accounts().collect::<Vec<_>>(),
+            vec![
+                (account_a_key, &"a".to_owned()),
+                (account_b_key, &"a".to_owned())
+            ],
+        );
+        // A move that refers to the same account on both sides is rejected.
+        let key_a = bincode::serialize(&account_a_key).unwrap();
+        let key_b = bincode::serialize(&account_b_key).unwrap();
+        let edited = replace(
+            &bytes,
+            &[&key_a[..], &key_b].concat(),
+            &[&key_a[..], &key_a].concat(),
+        );
+        match OwnedBook::from_bytes(&edited).err().unwrap() {
+            DecodeError::Invalid(violations) => assert_eq!(
+                violations,
+                vec![Violation::SameAccounts {
+                    transaction_index: TransactionIndex(0),
+                    move_index: MoveIndex(0),
+                }],
+            ),
+            error => panic!("unexpected error: {:?}", error),
+        }
+    }
 }
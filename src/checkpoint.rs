@@ -0,0 +1,122 @@
+use crate::{book::AccountKey, event::Event};
+use alloc::{
+    collections::BTreeSet,
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+/// Represents the accounts and the numbers of transactions and moves of a
+/// book at some point.
+///
+/// Created by [Book::checkpoint](crate::Book::checkpoint) and used by
+/// [Book::restore](crate::Book::restore).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub(crate) account_keys: BTreeSet<AccountKey>,
+    pub(crate) move_counts: Vec<usize>,
+    pub(crate) journal_len: usize,
+    pub(crate) serial: u64,
+    /// Keeps the journal recording while the checkpoint is alive.
+    pub(crate) live: Arc<()>,
+}
+/// Records the insertions and removals made to a book, while it has a live
+/// checkpoint.
+#[derive(Debug, Default)]
+pub(crate) struct Journal {
+    live: Weak<()>,
+    entries: Vec<(u64, Event)>,
+    next_serial: u64,
+}
+impl Journal {
+    pub(crate) fn record(&mut self, event: Event) {
+        let structural = matches!(
+            event,
+            Event::AccountInserted(_)
+                | Event::AccountRemoved(_)
+                | Event::TransactionInserted(_)
+                | Event::TransactionRemoved(_)
+                | Event::MoveInserted(..)
+                | Event::MoveRemoved(..)
+                | Event::Cleared
+        );
+        if self.live.strong_count() == 0 {
+            self.entries = Vec::new();
+        } else if structural {
+            self.entries.push((self.next_serial, event));
+            self.next_serial += 1;
+        }
+    }
+    pub(crate) fn checkpoint(
+        &mut self,
+        account_keys: BTreeSet<AccountKey>,
+        move_counts: Vec<usize>,
+    ) -> Checkpoint {
+        let live = self.live.upgrade().unwrap_or_else(|| {
+            self.entries = Vec::new();
+            let live = Arc::new(());
+            self.live = Arc::downgrade(&live);
+            live
+        });
+        Checkpoint {
+            account_keys,
+            move_counts,
+            journal_len: self.entries.len(),
+            serial: self.next_serial,
+            live,
+        }
+    }
+    /// Gets the events recorded since a checkpoint.
+    ///
+    /// ## Panics
+    ///
+    /// The checkpoint was undone by restoring an earlier one.
+    pub(crate) fn since(
+        &self,
+        checkpoint: &Checkpoint,
+    ) -> impl Iterator<Item = Event> + '_ {
+        let valid = checkpoint.journal_len == 0
+            || self
+                .entries
+                .get(checkpoint.journal_len - 1)
+                .map(|(serial, _)| serial + 1)
+                == Some(checkpoint.serial);
+        assert!(
+            valid,
+            "The checkpoint was undone by restoring an earlier one."
+        );
+        self.entries[checkpoint.journal_len..]
+            .iter()
+            .map(|(_, event)| *event)
+    }
+    pub(crate) fn truncate(&mut self, checkpoint: &Checkpoint) {
+        self.entries.truncate(checkpoint.journal_len);
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::Journal;
+    use crate::{book::TransactionIndex, event::Event};
+    use alloc::{collections::BTreeSet, vec::Vec};
+    #[test]
+    fn record_while_checkpoint_is_alive() {
+        let mut journal = Journal::default();
+        journal.record(Event::TransactionInserted(TransactionIndex(0)));
+        assert!(journal.entries.is_empty());
+        let checkpoint = journal.checkpoint(BTreeSet::new(), Vec::new());
+        let other_checkpoint = checkpoint.clone();
+        journal.record(Event::TransactionInserted(TransactionIndex(1)));
+        journal.record(Event::Cleared);
+        assert_eq!(journal.since(&checkpoint).count(), 2);
+        drop(checkpoint);
+        journal.record(Event::TransactionInserted(TransactionIndex(2)));
+        assert_eq!(journal.since(&other_checkpoint).count(), 3);
+        drop(other_checkpoint);
+        journal.record(Event::TransactionInserted(TransactionIndex(3)));
+        assert!(journal.entries.is_empty());
+        let checkpoint = journal.checkpoint(BTreeSet::new(), Vec::new());
+        journal.record(Event::TransactionInserted(TransactionIndex(4)));
+        assert_eq!(
+            journal.since(&checkpoint).collect::<Vec<_>>(),
+            [Event::TransactionInserted(TransactionIndex(4))],
+        );
+    }
+}
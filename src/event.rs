@@ -0,0 +1,42 @@
+use crate::{
+    book::{AccountKey, TransactionIndex},
+    move_::Side,
+    transaction::MoveIndex,
+};
+/// Represents a change made to a book.
+///
+/// Indexes are those of right after the change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Event {
+    #[allow(missing_docs)]
+    AccountInserted(AccountKey),
+    #[allow(missing_docs)]
+    AccountSet(AccountKey),
+    #[allow(missing_docs)]
+    AccountRemoved(AccountKey),
+    #[allow(missing_docs)]
+    AccountTagAdded(AccountKey),
+    #[allow(missing_docs)]
+    AccountTagRemoved(AccountKey),
+    #[allow(missing_docs)]
+    TransactionInserted(TransactionIndex),
+    #[allow(missing_docs)]
+    TransactionExtraSet(TransactionIndex),
+    #[allow(missing_docs)]
+    TransactionRemoved(TransactionIndex),
+    #[allow(missing_docs)]
+    MoveInserted(TransactionIndex, MoveIndex),
+    #[allow(missing_docs)]
+    MoveExtraSet(TransactionIndex, MoveIndex),
+    #[allow(missing_docs)]
+    MoveSumSet(TransactionIndex, MoveIndex),
+    #[allow(missing_docs)]
+    MoveSideSet(TransactionIndex, MoveIndex, Side),
+    #[allow(missing_docs)]
+    MoveRemoved(TransactionIndex, MoveIndex),
+    /// All accounts and transactions were removed.
+    Cleared,
+    /// The book was restored to a checkpoint.
+    Restored,
+}
@@ -0,0 +1,147 @@
+use crate::rounding_mode::RoundingMode;
+use alloc::collections::BTreeMap;
+use core::ops::{Add, Div, Rem, Sub};
+use num_traits::CheckedMul;
+/// Represents exchange rates between pairs of units.
+///
+/// A rate is the amount of the target unit that equals one of the source
+/// unit, as a fraction, so that a rate below one, such as `1/30`, can be
+/// expressed. Rates are directional; a rate from `a` to `b` does not imply a
+/// rate from `b` to `a`.
+#[derive(Debug, Clone)]
+pub struct ExchangeRates<Unit, Number>(
+    pub(crate) BTreeMap<Unit, BTreeMap<Unit, Ratio<Number>>>,
+)
+where
+    Unit: Ord;
+/// Represents a rate as a fraction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ratio<Number> {
+    #[allow(missing_docs)]
+    pub numerator: Number,
+    #[allow(missing_docs)]
+    pub denominator: Number,
+}
+impl<Number> Ratio<Number> {
+    /// Multiplies an amount by the ratio, rounding the product to a whole
+    /// number.
+    ///
+    /// Returns `None` if the multiplication overflows.
+    ///
+    /// ## Panics
+    ///
+    /// - The denominator is not positive.
+    pub(crate) fn apply(
+        &self,
+        amount: &Number,
+        mode: RoundingMode,
+    ) -> Option<Number>
+    where
+        Number: Clone
+            + Default
+            + PartialOrd
+            + From<u8>
+            + Add<Output = Number>
+            + Sub<Output = Number>
+            + CheckedMul
+            + Div<Output = Number>
+            + Rem<Output = Number>,
+    {
+        assert!(
+            self.denominator > Number::default(),
+            "Rate denominator is not positive."
+        );
+        let product = amount.checked_mul(&self.numerator)?;
+        Some(mode.divide(product, self.denominator.clone()))
+    }
+}
+impl<Unit, Number> ExchangeRates<Unit, Number>
+where
+    Unit: Ord,
+{
+    /// Sets the rate from one unit to another.
+    pub fn set_rate(&mut self, from: Unit, to: Unit, rate: Ratio<Number>) {
+        self.0.entry(from).or_default().insert(to, rate);
+    }
+    /// Gets the rate from one unit to another.
+    pub fn rate(&self, from: &Unit, to: &Unit) -> Option<&Ratio<Number>> {
+        self.0.get(from).and_then(|rates| rates.get(to))
+    }
+}
+impl<Unit, Number> Default for ExchangeRates<Unit, Number>
+where
+    Unit: Ord,
+{
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::{ExchangeRates, Ratio};
+    use crate::rounding_mode::RoundingMode;
+    #[test]
+    fn ratio_apply() {
+        let rate = Ratio {
+            numerator: 1,
+            denominator: 30,
+        };
+        assert_eq!(rate.apply(&45_i64, RoundingMode::HalfUp), Some(2));
+        assert_eq!(rate.apply(&45_i64, RoundingMode::TowardZero), Some(1));
+        assert_eq!(rate.apply(&-45_i64, RoundingMode::HalfUp), Some(-2));
+        let rate = Ratio {
+            numerator: 2,
+            denominator: 1,
+        };
+        assert_eq!(rate.apply(&u8::MAX, RoundingMode::HalfUp), None);
+    }
+    #[test]
+    #[should_panic(expected = "Rate denominator is not positive.")]
+    fn ratio_apply_denominator_not_positive() {
+        let rate = Ratio {
+            numerator: 1,
+            denominator: 0,
+        };
+        rate.apply(&1_u8, RoundingMode::HalfUp);
+    }
+    #[test]
+    fn default() {
+        let rates = ExchangeRates::<&str, u8>::default();
+        assert!(rates.0.is_empty());
+    }
+    fn ratio(numerator: u8, denominator: u8) -> Ratio<u8> {
+        Ratio {
+            numerator,
+            denominator,
+        }
+    }
+    #[test]
+    fn set_rate() {
+        let usd = "USD";
+        let thb = "THB";
+        let mut rates = ExchangeRates::default();
+        rates.set_rate(usd, thb, ratio(30, 1));
+        rates.set_rate(usd, thb, ratio(31, 1));
+        assert_eq!(rates.0[&usd][&thb], ratio(31, 1));
+    }
+    #[test]
+    fn rate() {
+        let usd = "USD";
+        let thb = "THB";
+        let mut rates = ExchangeRates::default();
+        rates.set_rate(thb, usd, ratio(1, 30));
+        assert_eq!(rates.rate(&thb, &usd), Some(&ratio(1, 30)));
+        assert_eq!(rates.rate(&usd, &thb), None);
+    }
+    #[test]
+    fn fmt_debug_clone() {
+        let usd = "USD";
+        let thb = "THB";
+        let mut rates = ExchangeRates::default();
+        rates.set_rate(thb, usd, ratio(1, 30));
+        assert_eq!(
+            format!("{:?}", rates.clone()),
+            r#"ExchangeRates({"THB": {"USD": Ratio { numerator: 1, denominator: 30 }}})"#,
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::fmt;
+use num_traits::CheckedMul;
+/// Represents the conventions of a locale for displaying numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    /// The character that separates the major from the minor units.
+    pub decimal_point: char,
+    /// The character that separates groups of three major digits, if any.
+    pub thousands_separator: Option<char>,
+}
+impl Locale {
+    /// The locale of the United States, as in `-1,234.56`.
+    pub const EN_US: Self = Self {
+        decimal_point: '.',
+        thousands_separator: Some(','),
+    };
+    /// The locale of Germany, as in `-1.234,56`.
+    pub const DE_DE: Self = Self {
+        decimal_point: ',',
+        thousands_separator: Some('.'),
+    };
+    const PLAIN: Self = Self {
+        decimal_point: '.',
+        thousands_separator: None,
+    };
+}
+/// Calculates ten to the power of `exponent`, such as the number of minor
+/// units in a major unit with `exponent` decimal places.
+///
+/// ## Panics
+///
+/// - The result overflows `Number`.
+pub(crate) fn power_of_ten<Number>(exponent: u8) -> Number
+where
+    Number: From<u8> + CheckedMul,
+{
+    (0..exponent).fold(Number::from(1), |power, _| {
+        power
+            .checked_mul(&Number::from(10))
+            .expect("Power of ten overflowed.")
+    })
+}
+/// Formats an amount of minor units with a decimal point.
+pub(crate) fn decimal(
+    amount: &impl fmt::Display,
+    decimal_places: u8,
+) -> String {
+    localized(amount, decimal_places, &Locale::PLAIN)
+}
+/// Formats an amount of minor units with a decimal point, right-aligned to
+/// `width`. A missing amount is formatted as zero.
+pub(crate) fn aligned<Number>(
+    amount: Option<&Number>,
+    width: usize,
+    decimal_places: u8,
+) -> String
+where
+    Number: Default + fmt::Display,
+{
+    let amount = match amount {
+        Some(amount) => decimal(amount, decimal_places),
+        None => decimal(&Number::default(), decimal_places),
+    };
+    format!("{:>width$}", amount, width = width)
+}
+/// Formats an amount of minor units by the conventions of a locale.
+///
+/// The sign always precedes the digits.
+pub(crate) fn localized(
+    amount: &impl fmt::Display,
+    decimal_places: u8,
+    locale: &Locale,
+) -> String {
+    let amount = amount.to_string();
+    let (sign, digits) = match amount.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", amount.as_str()),
+    };
+    let decimal_places = usize::from(decimal_places);
+    let digits = format!("{:0>width$}", digits, width = decimal_places + 1);
+    let (major, minor) = digits.split_at(digits.len() - decimal_places);
+    let mut formatted = sign.to_string();
+    major.chars().enumerate().for_each(|(index, digit)| {
+        if let Some(separator) = locale.thousands_separator {
+            if index > 0 && (major.len() - index) % 3 == 0 {
+                formatted.push(separator);
+            }
+        }
+        formatted.push(digit);
+    });
+    if !minor.is_empty() {
+        formatted.push(locale.decimal_point);
+        formatted += minor;
+    }
+    formatted
+}
+#[cfg(test)]
+mod test {
+    use super::{decimal, localized, power_of_ten, Locale};
+    #[test]
+    fn powers_of_ten() {
+        assert_eq!(power_of_ten::<u8>(0), 1);
+        assert_eq!(power_of_ten::<u8>(2), 100);
+        assert_eq!(power_of_ten::<i128>(38), 10_i128.pow(38));
+    }
+    #[test]
+    #[should_panic(expected = "Power of ten overflowed.")]
+    fn power_of_ten_overflow() {
+        power_of_ten::<i128>(39);
+    }
+    #[test]
+    fn decimal_places() {
+        assert_eq!(decimal(&1234, 2), "12.34");
+        assert_eq!(decimal(&-1234, 2), "-12.34");
+        assert_eq!(decimal(&5, 2), "0.05");
+        assert_eq!(decimal(&-5, 3), "-0.005");
+        assert_eq!(decimal(&0, 2), "0.00");
+    }
+    #[test]
+    fn no_decimal_places() {
+        assert_eq!(decimal(&1234, 0), "1234");
+        assert_eq!(decimal(&-1234, 0), "-1234");
+    }
+    #[test]
+    fn localized_en_us() {
+        let locale = Locale::EN_US;
+        assert_eq!(localized(&-123456, 2, &locale), "-1,234.56");
+        assert_eq!(localized(&123456789, 2, &locale), "1,234,567.89");
+        assert_eq!(localized(&12345, 2, &locale), "123.45");
+        assert_eq!(localized(&-5, 2, &locale), "-0.05");
+        assert_eq!(localized(&-1234567, 0, &locale), "-1,234,567");
+    }
+    #[test]
+    fn localized_de_de() {
+        let locale = Locale::DE_DE;
+        assert_eq!(localized(&-123456, 2, &locale), "-1.234,56");
+        assert_eq!(localized(&123456789, 2, &locale), "1.234.567,89");
+        assert_eq!(localized(&100000, 0, &locale), "100.000");
+    }
+}
@@ -0,0 +1,44 @@
+use crate::balance::Balance;
+/// Represents the income and expenses over a range of transactions.
+pub struct IncomeStatement<Unit, Number>
+where
+    Unit: Ord,
+{
+    pub(crate) income: Balance<Unit, Number>,
+    pub(crate) expenses: Balance<Unit, Number>,
+    pub(crate) net: Balance<Unit, Number>,
+}
+impl<Unit, Number> IncomeStatement<Unit, Number>
+where
+    Unit: Ord,
+{
+    /// Gets the amounts that moved out of income accounts.
+    pub fn income(&self) -> &Balance<Unit, Number> {
+        &self.income
+    }
+    /// Gets the amounts that moved into expense accounts.
+    pub fn expenses(&self) -> &Balance<Unit, Number> {
+        &self.expenses
+    }
+    /// Gets the income minus the expenses.
+    pub fn net(&self) -> &Balance<Unit, Number> {
+        &self.net
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::IncomeStatement;
+    use crate::test_utils::TestBalance;
+    #[test]
+    fn accessors() {
+        let usd = "USD";
+        let statement = IncomeStatement {
+            income: TestBalance::default() + &sum!(3, usd),
+            expenses: TestBalance::default() + &sum!(2, usd),
+            net: TestBalance::default() + &sum!(1, usd),
+        };
+        assert_eq!(statement.income(), &statement.income);
+        assert_eq!(statement.expenses(), &statement.expenses);
+        assert_eq!(statement.net(), &statement.net);
+    }
+}
@@ -1,4 +1,5 @@
 #![cfg_attr(feature = "fail-on-warnings", deny(warnings))]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 //#![deny(missing_docs)]
 #![deny(broken_intra_doc_links)]
 //#![deny(private_intra_doc_links)]
@@ -24,12 +25,19 @@
 //!
 //! Everything in this list is beyond the scope of this library:
 //!
-//! - Account types (asset/liability/income/expense/etc.)
-//! - Reports
+//! - Storing account types (asset/liability/income/expense/etc.). Reports
+//!   take the type of an account from a provided function instead.
+//! - Persistence. Books are kept in memory and storing them is up to the
+//!   user.
+//!
+//! ## Reports
+//!
+//! [Book::balance_sheet] writes a balance sheet of the accounts, as
+//! classified by [AccountClass]. [Book::income_statement] sums the income and
+//! expenses over a range of transactions into an [IncomeStatement].
 //!
 //! ## Todo
 //! - Cache balance calculations
-//! - Serialization
 //!
 //! ## Introduction
 //!
@@ -46,6 +54,21 @@
 //! would give the impression that function calls could fail even when used correctly.
 //! I'd like the user to be confident that with correct usage the API is safe.
 //!
+//! No interior mutability is used, so no panics can arise from borrowing.
+//! Reading from a book while mutating it is prevented by the borrow checker,
+//! at compile time.
+//!
+//! ## Serialization
+//!
+//! The `serde` feature implements serialization for books, sums and
+//! balances. The `bincode` feature adds `Book::to_bytes` and
+//! `Book::from_bytes`.
+//!
+//! ## `no_std`
+//!
+//! The `std` feature is enabled by default. Without it, this crate is
+//! `no_std` and only requires `alloc`.
+//!
 //! ## Get involved
 //!
 //! If you're using this crate, then please let me know—I'd be so happy!
@@ -56,6 +79,7 @@
 //!
 //! [ci]: https://img.shields.io/github/workflow/status/mightyiam/bookkeeping/Rust/master?logo=github
 //! [bookkeeping]: https://en.wikipedia.org/wiki/Bookkeeping
+extern crate alloc;
 #[macro_use]
 mod test_utils;
 macro_rules! introduction {
@@ -66,14 +90,43 @@ macro_rules! introduction {
 }
 introduction!(include_str!("../introduction.md"));
 mod balance;
+mod balance_sheet;
 mod book;
+mod checkpoint;
+mod event;
+mod exchange_rates;
+mod format;
+mod income_statement;
+mod merge_report;
 mod move_;
+mod query;
+mod remainder_policy;
+mod remove_report;
+mod rounding_mode;
 mod sum;
 mod transaction;
+mod violation;
+#[cfg(feature = "bincode")]
+pub use crate::book::DecodeError;
 pub use crate::{
-    balance::Balance,
-    book::{AccountKey, Book, TransactionIndex},
-    move_::{Move, Side},
-    sum::Sum,
+    balance::{Balance, TryFromBalanceError},
+    balance_sheet::AccountClass,
+    book::{
+        AccountKey, Book, InsertAccountError, ParseAccountKeyError,
+        TransactionIndex,
+    },
+    checkpoint::Checkpoint,
+    event::Event,
+    exchange_rates::{ExchangeRates, Ratio},
+    format::Locale,
+    income_statement::IncomeStatement,
+    merge_report::MergeReport,
+    move_::{Move, MoveBuilder, MoveBuilderError, MoveSpec, Side},
+    query::Query,
+    remainder_policy::RemainderPolicy,
+    remove_report::RemoveReport,
+    rounding_mode::RoundingMode,
+    sum::{ParseSumError, Sum, SumUnderflowError},
     transaction::{MoveIndex, Transaction},
+    violation::Violation,
 };
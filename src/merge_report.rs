@@ -0,0 +1,53 @@
+use crate::book::{AccountKey, TransactionIndex};
+use alloc::collections::BTreeMap;
+/// Reports how the contents of a merged book were inserted.
+pub struct MergeReport {
+    pub(crate) account_keys: BTreeMap<AccountKey, AccountKey>,
+    pub(crate) first_transaction_index: TransactionIndex,
+}
+impl MergeReport {
+    /// Gets the key of an inserted account by its key in the merged book.
+    pub fn account_key(
+        &self,
+        merged_account_key: AccountKey,
+    ) -> Option<AccountKey> {
+        self.account_keys.get(&merged_account_key).copied()
+    }
+    /// Gets an iterator of the keys of the accounts in the merged book, each
+    /// along with the key of the inserted account.
+    pub fn account_keys(
+        &self,
+    ) -> impl Iterator<Item = (AccountKey, AccountKey)> + '_ {
+        self.account_keys
+            .iter()
+            .map(|(merged_key, key)| (*merged_key, *key))
+    }
+    /// Gets the index of the first inserted transaction.
+    pub fn first_transaction_index(&self) -> TransactionIndex {
+        self.first_transaction_index
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::MergeReport;
+    use crate::{book::TransactionIndex, test_utils::TestBook};
+    use maplit::btreemap;
+    #[test]
+    fn accessors() {
+        let mut book = TestBook::default();
+        let merged_key = book.insert_account("");
+        let key = book.insert_account("");
+        let other_key = book.insert_account("");
+        let report = MergeReport {
+            account_keys: btreemap! { merged_key => key },
+            first_transaction_index: TransactionIndex(3),
+        };
+        assert_eq!(report.account_key(merged_key), Some(key));
+        assert_eq!(report.account_key(other_key), None);
+        assert_eq!(
+            report.account_keys().collect::<Vec<_>>(),
+            vec![(merged_key, key)],
+        );
+        assert_eq!(report.first_transaction_index(), TransactionIndex(3));
+    }
+}
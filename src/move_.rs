@@ -1,12 +1,117 @@
 use crate::{book::AccountKey, sum::Sum};
+use core::{error, fmt};
 /// Represents a side of a [Move].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Side {
     #[allow(missing_docs)]
     Debit,
     #[allow(missing_docs)]
     Credit,
 }
+/// Specifies a move to be created.
+pub struct MoveSpec<Unit, Number, Extra>
+where
+    Unit: Ord,
+{
+    #[allow(missing_docs)]
+    pub debit_account_key: AccountKey,
+    #[allow(missing_docs)]
+    pub credit_account_key: AccountKey,
+    #[allow(missing_docs)]
+    pub sum: Sum<Unit, Number>,
+    #[allow(missing_docs)]
+    pub extra: Extra,
+}
+/// Builds a [MoveSpec], with each of its parts set by name.
+pub struct MoveBuilder<Unit, Number, Extra>
+where
+    Unit: Ord,
+{
+    debit_account_key: Option<AccountKey>,
+    credit_account_key: Option<AccountKey>,
+    sum: Option<Sum<Unit, Number>>,
+    extra: Option<Extra>,
+}
+impl<Unit, Number, Extra> Default for MoveBuilder<Unit, Number, Extra>
+where
+    Unit: Ord,
+{
+    fn default() -> Self {
+        Self {
+            debit_account_key: None,
+            credit_account_key: None,
+            sum: None,
+            extra: None,
+        }
+    }
+}
+impl<Unit, Number, Extra> MoveBuilder<Unit, Number, Extra>
+where
+    Unit: Ord,
+{
+    /// Sets the debit account.
+    pub fn debit(mut self, account_key: AccountKey) -> Self {
+        self.debit_account_key = Some(account_key);
+        self
+    }
+    /// Sets the credit account.
+    pub fn credit(mut self, account_key: AccountKey) -> Self {
+        self.credit_account_key = Some(account_key);
+        self
+    }
+    /// Sets the sum.
+    pub fn sum(mut self, sum: Sum<Unit, Number>) -> Self {
+        self.sum = Some(sum);
+        self
+    }
+    /// Sets the extra data.
+    pub fn extra(mut self, extra: Extra) -> Self {
+        self.extra = Some(extra);
+        self
+    }
+    /// Builds the [MoveSpec], provided that all of its parts were set.
+    pub fn build(
+        self,
+    ) -> Result<MoveSpec<Unit, Number, Extra>, MoveBuilderError> {
+        Ok(MoveSpec {
+            debit_account_key: self
+                .debit_account_key
+                .ok_or(MoveBuilderError::MissingDebitAccount)?,
+            credit_account_key: self
+                .credit_account_key
+                .ok_or(MoveBuilderError::MissingCreditAccount)?,
+            sum: self.sum.ok_or(MoveBuilderError::MissingSum)?,
+            extra: self.extra.ok_or(MoveBuilderError::MissingExtra)?,
+        })
+    }
+}
+/// The error returned when building a [MoveSpec] with some part not set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MoveBuilderError {
+    #[allow(missing_docs)]
+    MissingDebitAccount,
+    #[allow(missing_docs)]
+    MissingCreditAccount,
+    #[allow(missing_docs)]
+    MissingSum,
+    #[allow(missing_docs)]
+    MissingExtra,
+}
+impl fmt::Display for MoveBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::MissingDebitAccount => "debit account not set",
+            Self::MissingCreditAccount => "credit account not set",
+            Self::MissingSum => "sum not set",
+            Self::MissingExtra => "extra data not set",
+        })
+    }
+}
+impl error::Error for MoveBuilderError {}
 /// Represents a move of a [Sum] from one account to another.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Move<Unit, Number, Extra>
 where
     Unit: Ord,
@@ -48,14 +153,35 @@ where
     pub fn sum(&self) -> &Sum<Unit, Number> {
         &self.sum
     }
+    /// Gets the amount of a unit in the sum of the move, which is the
+    /// [Default] of `Number` if the sum has no amount of the unit.
+    pub fn amount(&self, unit: &Unit) -> Number
+    where
+        Number: Default + Clone,
+    {
+        self.sum.0.get(unit).cloned().unwrap_or_default()
+    }
     /// Gets the extra data of the move.
     pub fn extra(&self) -> &Extra {
         &self.extra
     }
+    /// Compares moves by their accounts and sums, ignoring extra data.
+    ///
+    /// Sums are compared with [Sum::semantic_eq]. This finds moves that are
+    /// duplicates of each other, such as when importing overlapping
+    /// statements.
+    pub fn structural_eq(&self, other: &Self) -> bool
+    where
+        Number: Default + PartialEq,
+    {
+        self.debit_account_key == other.debit_account_key
+            && self.credit_account_key == other.credit_account_key
+            && self.sum.semantic_eq(&other.sum)
+    }
 }
 #[cfg(test)]
 mod test {
-    use super::{Move, Side};
+    use super::{Move, MoveBuilder, MoveBuilderError, Side};
     use crate::test_utils::TestBook;
     #[test]
     #[should_panic(expected = "Debit and credit accounts are the same.")]
@@ -65,6 +191,24 @@ mod test {
         Move::new(account_key, account_key, sum!(), ());
     }
     #[test]
+    fn structural_eq() {
+        let mut book = TestBook::default();
+        let a = book.insert_account("");
+        let b = book.insert_account("");
+        let usd = "USD";
+        let thb = "THB";
+        let move_ = Move::new(a, b, sum!(5, usd), "x");
+        assert!(move_.structural_eq(&Move::new(a, b, sum!(5, usd), "y")));
+        assert!(move_.structural_eq(&Move::new(
+            a,
+            b,
+            sum!(5, usd; 0, thb),
+            ""
+        )));
+        assert!(!move_.structural_eq(&Move::new(b, a, sum!(5, usd), "x")));
+        assert!(!move_.structural_eq(&Move::new(a, b, sum!(6, usd), "x")));
+    }
+    #[test]
     fn new() {
         let mut book = TestBook::default();
         let debit_account_key = book.insert_account("");
@@ -99,6 +243,32 @@ mod test {
         assert_eq!(*move_.sum(), sum);
     }
     #[test]
+    fn amount() {
+        let mut book = TestBook::default();
+        let debit_account_key = book.insert_account("");
+        let credit_account_key = book.insert_account("");
+        let thb = "THB";
+        let ils = "ILS";
+        let usd = "USD";
+        let move_ = Move::new(
+            debit_account_key,
+            credit_account_key,
+            sum!(100, thb),
+            "",
+        );
+        assert_eq!(move_.amount(&thb), 100);
+        assert_eq!(move_.amount(&ils), 0);
+        let move_ = Move::new(
+            debit_account_key,
+            credit_account_key,
+            sum!(100, thb; 200, ils),
+            "",
+        );
+        assert_eq!(move_.amount(&thb), 100);
+        assert_eq!(move_.amount(&ils), 200);
+        assert_eq!(move_.amount(&usd), 0);
+    }
+    #[test]
     fn extra() {
         let mut book = TestBook::default();
         let debit_account_key = book.insert_account("");
@@ -106,4 +276,60 @@ mod test {
         let move_ = Move::new(debit_account_key, credit_account_key, sum!(), 5);
         assert_eq!(*move_.extra(), 5);
     }
+    #[test]
+    fn builder() {
+        let mut book = TestBook::default();
+        let debit_account_key = book.insert_account("");
+        let credit_account_key = book.insert_account("");
+        let usd = "USD";
+        let spec = MoveBuilder::default()
+            .credit(credit_account_key)
+            .extra("a")
+            .sum(sum!(5, usd))
+            .debit(debit_account_key)
+            .build()
+            .unwrap();
+        assert_eq!(spec.debit_account_key, debit_account_key);
+        assert_eq!(spec.credit_account_key, credit_account_key);
+        assert_eq!(spec.sum, sum!(5, usd));
+        assert_eq!(spec.extra, "a");
+    }
+    #[test]
+    fn builder_missing_parts() {
+        let mut book = TestBook::default();
+        let account_key = book.insert_account("");
+        let build = |builder: MoveBuilder<&str, u64, ()>| builder.build().err();
+        assert_eq!(
+            build(MoveBuilder::default().credit(account_key)),
+            Some(MoveBuilderError::MissingDebitAccount),
+        );
+        assert_eq!(
+            build(MoveBuilder::default().debit(account_key).sum(sum!())),
+            Some(MoveBuilderError::MissingCreditAccount),
+        );
+        assert_eq!(
+            build(
+                MoveBuilder::default()
+                    .debit(account_key)
+                    .credit(account_key)
+            ),
+            Some(MoveBuilderError::MissingSum),
+        );
+        assert_eq!(
+            build(
+                MoveBuilder::default()
+                    .debit(account_key)
+                    .credit(account_key)
+                    .sum(sum!())
+            ),
+            Some(MoveBuilderError::MissingExtra),
+        );
+    }
+    #[test]
+    fn builder_error_fmt_display() {
+        assert_eq!(
+            MoveBuilderError::MissingCreditAccount.to_string(),
+            "credit account not set",
+        );
+    }
 }
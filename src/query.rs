@@ -0,0 +1,258 @@
+use crate::{
+    balance::Balance,
+    book::{AccountKey, Book, TransactionIndex},
+    move_::Move,
+    transaction::MoveIndex,
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::ops::{Add, Sub};
+/// Represents a query of the moves in a book.
+///
+/// Created by [Book::query]. Each filter narrows down the moves that the
+/// query matches.
+pub struct Query<'a, Unit, SumNumber, AccountExtra, TransactionExtra, MoveExtra>
+where
+    Unit: Ord,
+{
+    pub(crate) book:
+        &'a Book<Unit, SumNumber, AccountExtra, TransactionExtra, MoveExtra>,
+    pub(crate) account_key: Option<AccountKey>,
+    pub(crate) unit: Option<Unit>,
+    pub(crate) range: Option<(TransactionIndex, TransactionIndex)>,
+    #[allow(clippy::type_complexity)]
+    pub(crate) predicates:
+        Vec<Box<dyn Fn(&Move<Unit, SumNumber, MoveExtra>) -> bool + 'a>>,
+}
+impl<'a, Unit, SumNumber, AccountExtra, TransactionExtra, MoveExtra>
+    Query<'a, Unit, SumNumber, AccountExtra, TransactionExtra, MoveExtra>
+where
+    Unit: Ord,
+{
+    /// Matches only the moves into or out of an account.
+    ///
+    /// ## Panics
+    ///
+    /// - `account_key` is not in the book.
+    pub fn account(mut self, account_key: AccountKey) -> Self {
+        self.book.assert_has_account(account_key);
+        self.account_key = Some(account_key);
+        self
+    }
+    /// Matches only the moves whose sum has an amount of a unit.
+    pub fn unit(mut self, unit: Unit) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+    /// Matches only the moves in a range of transactions.
+    ///
+    /// Both `start` and `end` are inclusive.
+    ///
+    /// ## Panics
+    ///
+    /// - `start` is after `end`.
+    pub fn between(
+        mut self,
+        start: TransactionIndex,
+        end: TransactionIndex,
+    ) -> Self {
+        assert!(start <= end, "Start transaction is after end transaction.");
+        self.range = Some((start, end));
+        self
+    }
+    /// Matches only the moves for which `predicate` returns `true`.
+    ///
+    /// For example, moves can be marked as reconciled in their extra data,
+    /// and the balance of the unreconciled moves be calculated by filtering
+    /// on it.
+    pub fn filter(
+        mut self,
+        predicate: impl Fn(&Move<Unit, SumNumber, MoveExtra>) -> bool + 'a,
+    ) -> Self {
+        self.predicates.push(Box::new(predicate));
+        self
+    }
+    /// Gets an iterator of the matching moves, in the order of their
+    /// transactions and, within a transaction, in their order.
+    #[allow(clippy::type_complexity)]
+    pub fn moves(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            TransactionIndex,
+            MoveIndex,
+            &'a Move<Unit, SumNumber, MoveExtra>,
+        ),
+    > + '_ {
+        self.book.indexed_moves().filter(
+            move |(transaction_index, _, move_)| {
+                self.range.is_none_or(|(start, end)| {
+                    (start..=end).contains(transaction_index)
+                }) && self.account_key.is_none_or(|account_key| {
+                    move_.debit_account_key == account_key
+                        || move_.credit_account_key == account_key
+                }) && self
+                    .unit
+                    .as_ref()
+                    .is_none_or(|unit| move_.sum.0.contains_key(unit))
+                    && self.predicates.iter().all(|predicate| predicate(move_))
+            },
+        )
+    }
+    /// Calculates the balance of the account over the matching moves.
+    ///
+    /// When a unit is set, the balance has only amounts of that unit.
+    ///
+    /// ## Panics
+    ///
+    /// - No account is set.
+    pub fn balance<BalanceNumber>(&self) -> Balance<Unit, BalanceNumber>
+    where
+        Unit: Clone,
+        BalanceNumber: Default
+            + Sub<Output = BalanceNumber>
+            + Add<Output = BalanceNumber>
+            + Clone,
+        SumNumber: Clone + Into<BalanceNumber>,
+    {
+        let account_key = self
+            .account_key
+            .expect("No account set for the balance of a query.");
+        let mut balance = Balance::from_moves(
+            self.moves().map(|(_, _, move_)| move_),
+            account_key,
+        );
+        if let Some(unit) = &self.unit {
+            balance.0.retain(|balance_unit, _| balance_unit == unit);
+        }
+        balance
+    }
+}
+#[cfg(test)]
+mod test {
+    use crate::{
+        book::{AccountKey, TransactionIndex},
+        query::Query,
+        test_utils::{append_moves, TestBalance, TestBook},
+        transaction::MoveIndex,
+    };
+    fn book_with_moves() -> (TestBook, [AccountKey; 3]) {
+        let mut book = TestBook::default();
+        let a = book.insert_account("a");
+        let b = book.insert_account("b");
+        let c = book.insert_account("c");
+        let usd = "USD";
+        let thb = "THB";
+        append_moves(
+            &mut book,
+            &[
+                (0, a, b, sum!(1, usd), "0"),
+                (1, b, c, sum!(2, usd), "1"),
+                (2, c, a, sum!(4, usd; 8, thb), "2"),
+                (3, b, a, sum!(16, thb), "3"),
+            ],
+        );
+        (book, [a, b, c])
+    }
+    #[test]
+    fn moves() {
+        let (book, [a, _, _]) = book_with_moves();
+        let extras = |query: Query<_, _, _, _, _>| {
+            query
+                .moves()
+                .map(|(_, _, move_)| move_.extra)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(extras(book.query()), vec!["0", "1", "2", "3"]);
+        assert_eq!(extras(book.query().account(a)), vec!["0", "2", "3"]);
+        assert_eq!(extras(book.query().unit("THB")), vec!["2", "3"]);
+        assert_eq!(
+            extras(
+                book.query()
+                    .between(TransactionIndex(1), TransactionIndex(2))
+            ),
+            vec!["1", "2"],
+        );
+        assert_eq!(
+            extras(
+                book.query()
+                    .account(a)
+                    .unit("USD")
+                    .between(TransactionIndex(1), TransactionIndex(3))
+            ),
+            vec!["2"],
+        );
+    }
+    #[test]
+    fn balance() {
+        let (book, [a, _, _]) = book_with_moves();
+        let usd = "USD";
+        let thb = "THB";
+        assert_eq!(
+            book.query().account(a).balance::<i128>(),
+            TestBalance::default() - &sum!(1, usd) + &sum!(4, usd; 24, thb),
+        );
+        assert_eq!(
+            book.query()
+                .account(a)
+                .between(TransactionIndex(1), TransactionIndex(2))
+                .balance::<i128>(),
+            TestBalance::default() + &sum!(4, usd; 8, thb),
+        );
+        assert_eq!(
+            book.query()
+                .account(a)
+                .unit(usd)
+                .between(TransactionIndex(0), TransactionIndex(2))
+                .balance::<i128>(),
+            TestBalance::default() + &sum!(3, usd),
+        );
+    }
+    #[test]
+    fn filter() {
+        let (mut book, [a, _, _]) = book_with_moves();
+        let unreconciled = |book: &TestBook| {
+            book.query()
+                .account(a)
+                .filter(|move_| *move_.extra() != "reconciled")
+                .balance::<i128>()
+        };
+        let usd = "USD";
+        let thb = "THB";
+        assert_eq!(
+            unreconciled(&book),
+            TestBalance::default() - &sum!(1, usd) + &sum!(4, usd; 24, thb),
+        );
+        book.set_move_extra(TransactionIndex(2), MoveIndex(0), "reconciled");
+        assert_eq!(
+            unreconciled(&book),
+            TestBalance::default() - &sum!(1, usd) + &sum!(16, thb),
+        );
+        assert_eq!(
+            book.query()
+                .filter(|move_| move_.sum().0.len() == 1)
+                .filter(|move_| *move_.extra() != "0")
+                .moves()
+                .count(),
+            2,
+        );
+    }
+    #[test]
+    #[should_panic(expected = "No account set for the balance of a query.")]
+    fn balance_no_account() {
+        let (book, _) = book_with_moves();
+        book.query().balance::<i128>();
+    }
+    #[test]
+    #[should_panic(expected = "No account found for key ")]
+    fn account_not_found() {
+        let (_, [a, _, _]) = book_with_moves();
+        TestBook::default().query().account(a);
+    }
+    #[test]
+    #[should_panic(expected = "Start transaction is after end transaction.")]
+    fn between_start_after_end() {
+        let (book, _) = book_with_moves();
+        book.query()
+            .between(TransactionIndex(1), TransactionIndex(0));
+    }
+}
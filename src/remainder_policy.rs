@@ -0,0 +1,14 @@
+/// Represents a way of distributing what remains of an amount after
+/// splitting it in proportion to weights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemainderPolicy {
+    /// The remainder is added to the part with the largest weight, the first
+    /// one among equals.
+    LargestWeight,
+    /// The remainder is added to the first part.
+    FirstEntry,
+    /// The remainder is added one by one to the parts with a non-zero
+    /// weight, in their order. A negative remainder is added as minus one
+    /// each time.
+    Spread,
+}
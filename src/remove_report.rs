@@ -0,0 +1,41 @@
+/// Reports what became of the moves of a removed account, along with its
+/// extra data.
+pub struct RemoveReport<AccountExtra> {
+    pub(crate) extra: AccountExtra,
+    pub(crate) reassigned_moves: usize,
+    pub(crate) removed_moves: usize,
+}
+impl<AccountExtra> RemoveReport<AccountExtra> {
+    /// Gets the extra data of the removed account.
+    pub fn extra(&self) -> &AccountExtra {
+        &self.extra
+    }
+    /// Takes the extra data of the removed account.
+    pub fn into_extra(self) -> AccountExtra {
+        self.extra
+    }
+    /// Gets the number of moves that were reassigned to another account.
+    pub fn reassigned_moves(&self) -> usize {
+        self.reassigned_moves
+    }
+    /// Gets the number of moves that were removed.
+    pub fn removed_moves(&self) -> usize {
+        self.removed_moves
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::RemoveReport;
+    #[test]
+    fn accessors() {
+        let report = RemoveReport {
+            extra: "a",
+            reassigned_moves: 2,
+            removed_moves: 1,
+        };
+        assert_eq!(*report.extra(), "a");
+        assert_eq!(report.reassigned_moves(), 2);
+        assert_eq!(report.removed_moves(), 1);
+        assert_eq!(report.into_extra(), "a");
+    }
+}
@@ -0,0 +1,88 @@
+use core::ops::{Add, Div, Rem, Sub};
+/// Represents a way of rounding an amount to a whole number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Halves are rounded away from zero.
+    HalfUp,
+    /// Halves are rounded to the nearest even number.
+    HalfEven,
+    /// The fraction is dropped.
+    TowardZero,
+}
+impl RoundingMode {
+    /// Divides `amount` by a positive `divisor`, rounding the quotient.
+    pub(crate) fn divide<Number>(
+        self,
+        amount: Number,
+        divisor: Number,
+    ) -> Number
+    where
+        Number: Clone
+            + Default
+            + PartialOrd
+            + From<u8>
+            + Add<Output = Number>
+            + Sub<Output = Number>
+            + Div<Output = Number>
+            + Rem<Output = Number>,
+    {
+        let zero = Number::default();
+        let quotient = amount.clone() / divisor.clone();
+        let remainder = amount.clone() % divisor.clone();
+        let remainder = if remainder < zero {
+            zero.clone() - remainder
+        } else {
+            remainder
+        };
+        // Compared with what is left of the divisor, rather than doubled,
+        // which could overflow.
+        let rest_of_divisor = divisor - remainder.clone();
+        let away_from_zero = match self {
+            Self::HalfUp => remainder >= rest_of_divisor,
+            Self::HalfEven => {
+                remainder > rest_of_divisor
+                    || remainder == rest_of_divisor
+                        && quotient.clone() % Number::from(2) != zero
+            }
+            Self::TowardZero => false,
+        };
+        match (away_from_zero, amount < zero) {
+            (false, _) => quotient,
+            (true, false) => quotient + Number::from(1),
+            (true, true) => quotient - Number::from(1),
+        }
+    }
+}
+#[cfg(test)]
+mod test {
+    use super::RoundingMode::{HalfEven, HalfUp, TowardZero};
+    #[test]
+    fn divide() {
+        [
+            (HalfUp, 25, 3),
+            (HalfUp, 35, 4),
+            (HalfUp, 24, 2),
+            (HalfUp, -25, -3),
+            (HalfEven, 25, 2),
+            (HalfEven, 35, 4),
+            (HalfEven, 26, 3),
+            (HalfEven, -25, -2),
+            (HalfEven, -26, -3),
+            (TowardZero, 29, 2),
+            (TowardZero, -29, -2),
+        ]
+        .iter()
+        .for_each(|(mode, amount, expected)| {
+            assert_eq!(mode.divide(*amount, 10_i64), *expected, "{:?}", mode);
+        });
+    }
+    #[test]
+    fn divide_near_max() {
+        let divisor = i128::MAX / 2 + 1;
+        assert_eq!(HalfUp.divide(i128::MAX / 2 + 1, divisor), 1);
+        assert_eq!(HalfUp.divide(i128::MAX - 1, divisor), 2);
+        assert_eq!(HalfUp.divide(-(i128::MAX - 1), divisor), -2);
+        assert_eq!(HalfEven.divide(9 * 10_i128.pow(37), 10_i128.pow(38)), 1);
+        assert_eq!(HalfUp.divide(4 * 10_i128.pow(37), 10_i128.pow(38)), 0);
+    }
+}
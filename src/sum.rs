@@ -1,6 +1,35 @@
-use std::{collections::BTreeMap, fmt};
+use crate::{
+    exchange_rates::{ExchangeRates, Ratio},
+    format,
+    remainder_policy::RemainderPolicy,
+    rounding_mode::RoundingMode,
+};
+use alloc::{borrow::ToOwned, collections::BTreeMap, string::String, vec::Vec};
+use core::{
+    error, fmt,
+    iter::FromIterator,
+    ops::{Add, Div, Rem, Sub},
+    str::FromStr,
+};
+use num_traits::{CheckedAdd, CheckedMul, CheckedSub};
 /// Represents amounts of any number of units.
+///
+/// The sums of moves are best kept to an unsigned number type, such as
+/// `u64`, as the direction of a move is given by its debit and credit
+/// accounts. A sum of a signed number type, such as `i128`, can hold negative
+/// amounts, like a short position, and converts into a
+/// [Balance](crate::Balance) of the same number type. To insert a signed
+/// amount as a move, see
+/// [Book::insert_move_signed](crate::Book::insert_move_signed).
+///
+/// With the `serde` feature, a sum serializes as a map from units to
+/// amounts.
 #[derive(Clone, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Sum<Unit, Number>(pub(crate) BTreeMap<Unit, Number>)
 where
     Unit: Ord;
@@ -8,6 +37,66 @@ impl<Unit, Number> Sum<Unit, Number>
 where
     Unit: Ord,
 {
+    /// Creates a sum of an amount of a single unit.
+    pub fn of(unit: Unit, amount: Number) -> Self {
+        Self(BTreeMap::new()).set_unit(unit, amount)
+    }
+    /// Adds an amount of a unit to the sum.
+    ///
+    /// The amount is added to any amount of the unit already in the sum.
+    ///
+    /// ## Panics
+    ///
+    /// - The addition overflows.
+    pub fn unit(mut self, unit: Unit, amount: Number) -> Self
+    where
+        Number: CheckedAdd,
+    {
+        let amount = match self.0.remove(&unit) {
+            Some(existing) => existing
+                .checked_add(&amount)
+                .expect("Amount of unit overflowed."),
+            None => amount,
+        };
+        self.0.insert(unit, amount);
+        self
+    }
+    /// Sets the amount of a unit in the sum, replacing any amount of the unit
+    /// already in it.
+    ///
+    /// This is the builder form of [Sum::set_amount_for_unit], which is
+    /// equivalent.
+    pub fn set_unit(mut self, unit: Unit, amount: Number) -> Self {
+        self.set_amount_for_unit(amount, unit);
+        self
+    }
+    /// Subtracts another sum from this one.
+    ///
+    /// A unit missing from this sum is taken to have an amount of zero, which
+    /// is the [Default] of `Number`. Units remain in the result even where
+    /// their amount becomes zero.
+    ///
+    /// Fails with the first unit whose amount would underflow, such as go
+    /// below zero for an unsigned number type.
+    pub fn checked_sub(
+        &self,
+        other: &Self,
+    ) -> Result<Self, SumUnderflowError<Unit>>
+    where
+        Unit: Clone,
+        Number: Clone + Default + CheckedSub,
+    {
+        let mut difference = self.clone();
+        other.0.iter().try_for_each(|(unit, amount)| {
+            let existing = difference.0.remove(unit).unwrap_or_default();
+            let amount = existing
+                .checked_sub(amount)
+                .ok_or_else(|| SumUnderflowError { unit: unit.clone() })?;
+            difference.0.insert(unit.clone(), amount);
+            Ok(())
+        })?;
+        Ok(difference)
+    }
     /// Sets the amount of a unit in a sum.
     pub fn set_amount_for_unit(&mut self, amount: Number, unit_: Unit) {
         self.0.insert(unit_, amount);
@@ -19,6 +108,488 @@ where
     pub fn unit_amount(&self, unit: &Unit) -> Option<&Number> {
         self.0.get(unit)
     }
+    /// Splits the amount of a unit into major and minor units, such as `1234`
+    /// with `2` decimal places into `(12, 34)`.
+    ///
+    /// Returns `None` if the sum has no amount of `unit`. With `0` decimal
+    /// places, the minor units are always zero.
+    ///
+    /// ## Panics
+    ///
+    /// - The number of minor units in a major unit overflows `Number`.
+    pub fn major_minor(
+        &self,
+        unit: &Unit,
+        decimal_places: u8,
+    ) -> Option<(Number, Number)>
+    where
+        Number: Clone
+            + From<u8>
+            + CheckedMul
+            + Div<Output = Number>
+            + Rem<Output = Number>,
+    {
+        let minor_per_major = format::power_of_ten::<Number>(decimal_places);
+        self.0.get(unit).map(|amount| {
+            (
+                amount.clone() / minor_per_major.clone(),
+                amount.clone() % minor_per_major,
+            )
+        })
+    }
+    /// Formats the amount of a unit with a decimal point, right-aligned to a
+    /// column of `width` characters, such as `    12.34`.
+    ///
+    /// The amount is taken to be in minor units, with `decimal_places` of
+    /// them making up the major unit. A missing unit is formatted as zero,
+    /// which is the [Default] of `Number`. An amount longer than `width` is
+    /// not truncated.
+    pub fn format_aligned(
+        &self,
+        unit: &Unit,
+        width: usize,
+        decimal_places: u8,
+    ) -> String
+    where
+        Number: Default + fmt::Display,
+    {
+        format::aligned(self.0.get(unit), width, decimal_places)
+    }
+    /// Compares sums by the amounts they represent, treating a missing unit
+    /// as having an amount of zero.
+    ///
+    /// Unlike `==`, this considers a sum with an explicit zero amount of a
+    /// unit equal to a sum without that unit. Zero is taken to be the
+    /// [Default] of `Number`.
+    pub fn semantic_eq(&self, other: &Self) -> bool
+    where
+        Number: Default + PartialEq,
+    {
+        let zero = Number::default();
+        let is_subset = |a: &Self, b: &Self| {
+            a.0.iter()
+                .all(|(unit, amount)| b.0.get(unit).unwrap_or(&zero) == amount)
+        };
+        is_subset(self, other) && is_subset(other, self)
+    }
+    /// Retains only the units for which `predicate` returns `true`.
+    pub fn retain(
+        &mut self,
+        mut predicate: impl FnMut(&Unit, &Number) -> bool,
+    ) {
+        self.0.retain(|unit, amount| predicate(unit, amount));
+    }
+    /// Removes the units whose amount is zero.
+    ///
+    /// Zero is taken to be the [Default] of `Number`.
+    pub fn normalize(&mut self)
+    where
+        Number: Default + PartialEq,
+    {
+        let zero = Number::default();
+        self.0.retain(|_, amount| *amount != zero);
+    }
+    /// Gets the sum without the units whose amount is zero.
+    ///
+    /// See [Sum::normalize].
+    pub fn normalized(mut self) -> Self
+    where
+        Number: Default + PartialEq,
+    {
+        self.normalize();
+        self
+    }
+    /// Splits the sum into parts, in proportion to weights.
+    ///
+    /// Each amount is divided by the weights, rounding toward zero. What
+    /// remains of an amount due to rounding is added to the part with the
+    /// largest weight, the first one among equals. So, the parts always add
+    /// up to the sum.
+    ///
+    /// ## Panics
+    ///
+    /// - The weights add up to zero.
+    /// - Some amount multiplied by some weight overflows.
+    pub fn split_proportional<Key>(
+        &self,
+        weights: &[(Key, Number)],
+    ) -> Vec<(Key, Self)>
+    where
+        Unit: Clone,
+        Key: Clone,
+        Number: Clone
+            + Default
+            + PartialOrd
+            + Add<Output = Number>
+            + Sub<Output = Number>
+            + CheckedMul
+            + Div<Output = Number>,
+    {
+        let largest_weight_index = largest_weight_index(weights);
+        self.split(weights, |parts, unit, remainder| {
+            parts[largest_weight_index].1.add_amount(unit, remainder);
+        })
+    }
+    /// Splits the sum into parts, in proportion to weights, distributing
+    /// what remains of each amount by a policy.
+    ///
+    /// Like with [Sum::split_proportional], each amount is divided by the
+    /// weights, rounding toward zero, and the parts always add up to the sum.
+    ///
+    /// ## Panics
+    ///
+    /// - The weights add up to zero.
+    /// - Some amount multiplied by some weight overflows.
+    pub fn split_proportional_with<Key>(
+        &self,
+        weights: &[(Key, Number)],
+        remainder_policy: RemainderPolicy,
+    ) -> Vec<(Key, Self)>
+    where
+        Unit: Clone,
+        Key: Clone,
+        Number: Clone
+            + Default
+            + PartialOrd
+            + From<u8>
+            + Add<Output = Number>
+            + Sub<Output = Number>
+            + CheckedMul
+            + Div<Output = Number>,
+    {
+        let largest_weight_index = largest_weight_index(weights);
+        self.split(weights, |parts, unit, mut remainder| match remainder_policy
+        {
+            RemainderPolicy::LargestWeight => {
+                parts[largest_weight_index].1.add_amount(unit, remainder);
+            }
+            RemainderPolicy::FirstEntry => {
+                parts[0].1.add_amount(unit, remainder);
+            }
+            RemainderPolicy::Spread => {
+                let zero = Number::default();
+                let step = if remainder < zero {
+                    zero.clone() - Number::from(1)
+                } else {
+                    Number::from(1)
+                };
+                let mut indexes = weights
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, weight))| *weight != zero)
+                    .map(|(index, _)| index)
+                    .cycle();
+                while remainder != zero {
+                    let index = indexes.next().unwrap();
+                    parts[index].1.add_amount(unit, step.clone());
+                    remainder = remainder - step.clone();
+                }
+            }
+        })
+    }
+    fn split<Key>(
+        &self,
+        weights: &[(Key, Number)],
+        mut add_remainder: impl FnMut(&mut [(Key, Self)], &Unit, Number),
+    ) -> Vec<(Key, Self)>
+    where
+        Unit: Clone,
+        Key: Clone,
+        Number: Clone
+            + Default
+            + PartialOrd
+            + Add<Output = Number>
+            + Sub<Output = Number>
+            + CheckedMul
+            + Div<Output = Number>,
+    {
+        let total_weight = weights
+            .iter()
+            .fold(Number::default(), |total, (_, weight)| {
+                total + weight.clone()
+            });
+        assert!(total_weight != Number::default(), "Weights add up to zero.");
+        let mut parts = weights
+            .iter()
+            .map(|(key, _)| (key.clone(), Self(BTreeMap::new())))
+            .collect::<Vec<_>>();
+        self.0.iter().for_each(|(unit, amount)| {
+            let mut remainder = amount.clone();
+            weights.iter().zip(parts.iter_mut()).for_each(
+                |((_, weight), (_, part))| {
+                    let share = amount
+                        .checked_mul(weight)
+                        .expect("Split amount overflowed.")
+                        / total_weight.clone();
+                    remainder = remainder.clone() - share.clone();
+                    part.0.insert(unit.clone(), share);
+                },
+            );
+            add_remainder(&mut parts, unit, remainder);
+        });
+        parts
+    }
+    fn add_amount(&mut self, unit: &Unit, amount: Number)
+    where
+        Unit: Clone,
+        Number: Add<Output = Number>,
+    {
+        let share = self.0.remove(unit).unwrap();
+        self.0.insert(unit.clone(), share + amount);
+    }
+    /// Parses a sum from text such as `100 USD, 50 EUR`.
+    ///
+    /// The text is a comma separated list of amount and unit pairs.
+    /// Units are resolved from their text using `resolve_unit`.
+    /// Amounts of a unit that appears more than once are added together,
+    /// failing if that overflows.
+    /// Blank text is parsed as an empty sum.
+    pub fn parse(
+        text: &str,
+        resolve_unit: impl Fn(&str) -> Option<Unit>,
+    ) -> Result<Self, ParseSumError>
+    where
+        Number: FromStr + CheckedAdd,
+    {
+        let mut sum = Self(BTreeMap::new());
+        if text.trim().is_empty() {
+            return Ok(sum);
+        }
+        text.split(',').enumerate().try_for_each(|(index, token)| {
+            let token = token.trim();
+            let mut parts = token.split_whitespace();
+            let (amount, unit) =
+                match (parts.next(), parts.next(), parts.next()) {
+                    (Some(amount), Some(unit), None) => (amount, unit),
+                    _ => {
+                        return Err(ParseSumError::InvalidToken {
+                            index,
+                            token: token.to_owned(),
+                        })
+                    }
+                };
+            let amount = amount.parse::<Number>().map_err(|_| {
+                ParseSumError::InvalidAmount {
+                    index,
+                    token: token.to_owned(),
+                }
+            })?;
+            let unit = resolve_unit(unit).ok_or_else(|| {
+                ParseSumError::UnknownUnit {
+                    index,
+                    token: token.to_owned(),
+                }
+            })?;
+            let amount =
+                match sum.0.get(&unit) {
+                    Some(existing) => existing
+                        .checked_add(&amount)
+                        .ok_or_else(|| ParseSumError::AmountOverflow {
+                            index,
+                            token: token.to_owned(),
+                        })?,
+                    None => amount,
+                };
+            sum.0.insert(unit, amount);
+            Ok(())
+        })?;
+        Ok(sum)
+    }
+    /// Creates a sum in which the amount of one unit is converted into
+    /// another unit.
+    ///
+    /// The amount of `from` is multiplied by `rate` and added to the amount
+    /// of `to`. The amount of `from` is then removed.
+    ///
+    /// The converted amount is rounded to a whole number by `mode`.
+    ///
+    /// If the sum has no amount of `from`, the result is equal to the sum.
+    ///
+    /// ## Panics
+    ///
+    /// - The `rate` denominator is not positive.
+    /// - The converted amount, or its addition to the amount of `to`,
+    ///   overflows.
+    pub fn convert(
+        &self,
+        from: &Unit,
+        to: Unit,
+        rate: &Ratio<Number>,
+        mode: RoundingMode,
+    ) -> Self
+    where
+        Unit: Clone,
+        Number: Clone
+            + Default
+            + PartialOrd
+            + From<u8>
+            + Add<Output = Number>
+            + Sub<Output = Number>
+            + CheckedAdd
+            + CheckedMul
+            + Div<Output = Number>
+            + Rem<Output = Number>,
+    {
+        let mut converted = self.clone();
+        if let Some(amount) = converted.0.remove(from) {
+            let amount = rate
+                .apply(&amount, mode)
+                .expect("Converted amount overflowed.");
+            converted = converted.unit(to, amount);
+        }
+        converted
+    }
+    /// Totals the sum in a single unit, such as the worth of an invoice of
+    /// several currencies in one of them.
+    ///
+    /// Like [Balance::value_in](crate::Balance::value_in).
+    /// Amounts of the `target` unit are taken as they are.
+    /// Amounts of any other unit are multiplied by the rate from that unit to
+    /// the `target` unit, each rounded to a whole number by `mode`.
+    ///
+    /// Returns `None` if a rate from some unit in the sum to the `target` unit
+    /// is missing, or if the value overflows.
+    ///
+    /// ## Panics
+    ///
+    /// - The denominator of some rate that is used is not positive.
+    pub fn total_in(
+        &self,
+        target: &Unit,
+        rates: &ExchangeRates<Unit, Number>,
+        mode: RoundingMode,
+    ) -> Option<Number>
+    where
+        Number: Clone
+            + Default
+            + PartialOrd
+            + From<u8>
+            + Add<Output = Number>
+            + Sub<Output = Number>
+            + CheckedAdd
+            + CheckedMul
+            + Div<Output = Number>
+            + Rem<Output = Number>,
+    {
+        self.0
+            .iter()
+            .try_fold(Number::default(), |value, (unit, amount)| {
+                if unit == target {
+                    value.checked_add(amount)
+                } else {
+                    let amount =
+                        rates.rate(unit, target)?.apply(amount, mode)?;
+                    value.checked_add(&amount)
+                }
+            })
+    }
+}
+/// Gets the index of the largest weight, the first one among equals.
+fn largest_weight_index<Key, Number>(weights: &[(Key, Number)]) -> usize
+where
+    Number: PartialOrd,
+{
+    weights
+        .iter()
+        .enumerate()
+        .fold(0, |largest, (index, (_, weight))| {
+            if *weight > weights[largest].1 {
+                index
+            } else {
+                largest
+            }
+        })
+}
+/// The error returned when subtracting one [Sum] from another underflows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SumUnderflowError<Unit> {
+    /// The unit whose amount would be out of the range of the number type,
+    /// for example below zero.
+    pub unit: Unit,
+}
+impl<Unit> fmt::Display for SumUnderflowError<Unit>
+where
+    Unit: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "amount of unit {:?} underflowed", self.unit)
+    }
+}
+impl<Unit> error::Error for SumUnderflowError<Unit> where Unit: fmt::Debug {}
+/// An error which can be returned when parsing a [Sum].
+///
+/// Each variant holds the offending token, trimmed, and its zero-based index
+/// among the comma separated tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseSumError {
+    /// The token is not an amount followed by a unit.
+    InvalidToken {
+        /// The index of the token.
+        index: usize,
+        /// The token, trimmed.
+        token: String,
+    },
+    /// The amount in the token could not be parsed.
+    InvalidAmount {
+        /// The index of the token.
+        index: usize,
+        /// The token, trimmed.
+        token: String,
+    },
+    /// The unit in the token could not be resolved.
+    UnknownUnit {
+        /// The index of the token.
+        index: usize,
+        /// The token, trimmed.
+        token: String,
+    },
+    /// Adding the amount in the token to the amount of its unit overflowed.
+    AmountOverflow {
+        /// The index of the token.
+        index: usize,
+        /// The token, trimmed.
+        token: String,
+    },
+}
+impl fmt::Display for ParseSumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidToken { index, token } => write!(
+                f,
+                "expected an amount and a unit in token {} {:?}",
+                index, token
+            ),
+            Self::InvalidAmount { index, token } => {
+                write!(f, "invalid amount in token {} {:?}", index, token)
+            }
+            Self::UnknownUnit { index, token } => {
+                write!(f, "unknown unit in token {} {:?}", index, token)
+            }
+            Self::AmountOverflow { index, token } => {
+                write!(f, "amount overflowed in token {} {:?}", index, token)
+            }
+        }
+    }
+}
+impl error::Error for ParseSumError {}
+/// Amounts of the same unit are added, like with [Sum::unit].
+///
+/// ## Panics
+///
+/// - An addition overflows.
+impl<Unit, Number> FromIterator<(Unit, Number)> for Sum<Unit, Number>
+where
+    Unit: Ord,
+    Number: CheckedAdd,
+{
+    fn from_iter<T: IntoIterator<Item = (Unit, Number)>>(iter: T) -> Self {
+        iter.into_iter()
+            .fold(Self(BTreeMap::new()), |sum, (unit, amount)| {
+                sum.unit(unit, amount)
+            })
+    }
 }
 impl<Unit, Number> fmt::Debug for Sum<Unit, Number>
 where
@@ -33,9 +604,141 @@ where
 }
 #[cfg(test)]
 mod test {
-    use super::Sum;
+    use super::{
+        ParseSumError, RemainderPolicy, RoundingMode, Sum, SumUnderflowError,
+    };
+    use crate::{
+        balance::Balance,
+        exchange_rates::{ExchangeRates, Ratio},
+    };
     use maplit::btreemap;
     #[test]
+    fn of() {
+        let usd = "USD";
+        assert_eq!(Sum::of(usd, 2), sum!(2, usd));
+    }
+    #[test]
+    fn unit() {
+        let usd = "USD";
+        let thb = "THB";
+        assert_eq!(Sum::of(usd, 2).unit(usd, 3), sum!(5, usd));
+        assert_eq!(Sum::of(usd, 2).unit(thb, 3), sum!(2, usd; 3, thb));
+    }
+    #[test]
+    #[should_panic(expected = "Amount of unit overflowed.")]
+    fn unit_overflow() {
+        let usd = "USD";
+        Sum::of(usd, u64::MAX).unit(usd, 1);
+    }
+    #[test]
+    fn from_iter() {
+        let usd = "USD";
+        let thb = "THB";
+        let sum = vec![(usd, 2), (thb, 3), (usd, 4)]
+            .into_iter()
+            .collect::<Sum<_, u64>>();
+        assert_eq!(sum, sum!(6, usd; 3, thb));
+    }
+    #[test]
+    fn total_in() {
+        let usd = "USD";
+        let thb = "THB";
+        let ils = "ILS";
+        let sum = sum!(2, usd; 100, thb);
+        let mut rates = ExchangeRates::default();
+        let rate = |numerator, denominator| Ratio {
+            numerator,
+            denominator,
+        };
+        rates.set_rate(usd, thb, rate(30, 1));
+        let mode = RoundingMode::HalfUp;
+        assert_eq!(sum.total_in(&thb, &rates, mode), Some(160));
+        assert_eq!(sum.total_in(&ils, &rates, mode), None);
+        rates.set_rate(thb, usd, rate(1, 30));
+        assert_eq!(sum.total_in(&usd, &rates, mode), Some(5));
+        assert_eq!(
+            sum.total_in(&usd, &rates, RoundingMode::TowardZero),
+            Some(5),
+        );
+        let sum = sum!(2, usd; 44, thb);
+        assert_eq!(sum.total_in(&usd, &rates, mode), Some(3));
+        assert_eq!(
+            sum.total_in(&usd, &rates, RoundingMode::TowardZero),
+            Some(3),
+        );
+        rates.set_rate(usd, thb, rate(u64::MAX, 1));
+        assert_eq!(sum.total_in(&thb, &rates, mode), None);
+    }
+    #[test]
+    fn signed_into_balance() {
+        let usd = "USD";
+        let thb = "THB";
+        let sum = Sum::of(usd, -5_i128).unit(thb, 3);
+        assert_eq!(
+            Balance::from(&sum),
+            Balance(btreemap! { usd => -5, thb => 3 }),
+        );
+    }
+    #[test]
+    fn major_minor() {
+        let usd = "USD";
+        let jpy = "JPY";
+        let sum = sum!(1234, usd; 1234, jpy);
+        assert_eq!(sum.major_minor(&usd, 2), Some((12, 34)));
+        assert_eq!(sum.major_minor(&jpy, 0), Some((1234, 0)));
+        assert_eq!(sum.major_minor(&"EUR", 2), None);
+    }
+    #[test]
+    #[should_panic(expected = "Power of ten overflowed.")]
+    fn major_minor_overflow() {
+        let usd = "USD";
+        sum!(1, usd).major_minor(&usd, 20);
+    }
+    #[test]
+    fn format_aligned() {
+        let usd = "USD";
+        let thb = "THB";
+        let sum = sum!(1234567, usd; 5, thb);
+        assert_eq!(sum.format_aligned(&usd, 10, 2), "  12345.67");
+        assert_eq!(sum.format_aligned(&thb, 10, 2), "      0.05");
+        assert_eq!(sum.format_aligned(&"ILS", 10, 2), "      0.00");
+        assert_eq!(sum.format_aligned(&usd, 4, 0), "1234567");
+    }
+    #[test]
+    fn checked_sub() {
+        let usd = "USD";
+        let thb = "THB";
+        let eur = "EUR";
+        let sum = sum!(5, usd; 3, thb; 1, eur);
+        assert_eq!(
+            sum.checked_sub(&sum!(2, usd; 3, thb)),
+            Ok(sum!(3, usd; 0, thb; 1, eur)),
+        );
+        assert_eq!(
+            sum.checked_sub(&sum!(2, usd; 4, thb)),
+            Err(SumUnderflowError { unit: thb }),
+        );
+        assert_eq!(
+            sum.checked_sub(&sum!(1, usd; 1, eur; 1, thb)),
+            Ok(sum!(4, usd; 2, thb; 0, eur)),
+        );
+        let ils = "ILS";
+        assert_eq!(
+            sum.checked_sub(&sum!(1, ils)),
+            Err(SumUnderflowError { unit: ils }),
+        );
+    }
+    #[test]
+    fn sum_underflow_error_fmt_display() {
+        let error = SumUnderflowError { unit: "USD" };
+        assert_eq!(error.to_string(), "amount of unit \"USD\" underflowed");
+    }
+    #[test]
+    fn set_unit() {
+        let usd = "USD";
+        assert_eq!(Sum::of(usd, 2).set_unit(usd, 3), sum!(3, usd));
+    }
+    #[test]
     fn default() {
         let actual = Sum::<&str, usize>::default();
         let expected = Sum(btreemap! {});
@@ -92,4 +795,247 @@ mod test {
         );
         assert_eq!(actual, expected);
     }
+    #[test]
+    fn convert() {
+        let usd = "USD";
+        let thb = "THB";
+        let ils = "ILS";
+        let rate = Ratio {
+            numerator: 10,
+            denominator: 3,
+        };
+        let sum = sum!(1, usd; 5, ils);
+        assert_eq!(
+            sum.convert(&usd, thb, &rate, RoundingMode::HalfUp),
+            sum!(3, thb; 5, ils),
+        );
+        let sum = sum!(2, usd; 5, thb);
+        assert_eq!(
+            sum.convert(&usd, thb, &rate, RoundingMode::HalfUp),
+            sum!(12, thb),
+        );
+        let sum = sum!(5, thb);
+        assert_eq!(sum.convert(&usd, thb, &rate, RoundingMode::HalfUp), sum);
+    }
+    #[test]
+    fn convert_rounding() {
+        let usd = "USD";
+        let thb = "THB";
+        let rate = Ratio {
+            numerator: 71,
+            denominator: 2,
+        };
+        [
+            (1, RoundingMode::HalfUp, 36),
+            (3, RoundingMode::HalfUp, 107),
+            (1, RoundingMode::HalfEven, 36),
+            (3, RoundingMode::HalfEven, 106),
+            (1, RoundingMode::TowardZero, 35),
+        ]
+        .iter()
+        .for_each(|(amount, mode, expected)| {
+            assert_eq!(
+                sum!(*amount, usd).convert(&usd, thb, &rate, *mode),
+                sum!(*expected, thb),
+                "{:?}",
+                mode,
+            );
+        });
+    }
+    #[test]
+    #[should_panic(expected = "Converted amount overflowed.")]
+    fn convert_overflow() {
+        let usd = "USD";
+        let thb = "THB";
+        let rate = Ratio {
+            numerator: 3,
+            denominator: 1,
+        };
+        sum!(u64::MAX / 2, usd).convert(&usd, thb, &rate, RoundingMode::HalfUp);
+    }
+    #[test]
+    fn semantic_eq() {
+        let a = "A";
+        let b = "B";
+        assert!(sum!(0, a).semantic_eq(&sum!()));
+        assert!(sum!().semantic_eq(&sum!(0, a)));
+        assert!(sum!(5, a).semantic_eq(&sum!(5, a; 0, b)));
+        assert!(!sum!(5, a).semantic_eq(&sum!(5, a; 1, b)));
+        assert!(!sum!(5, a).semantic_eq(&sum!(4, a)));
+    }
+    #[test]
+    fn retain() {
+        let usd = "USD";
+        let thb = "THB";
+        let ils = "ILS";
+        let mut sum = sum!(1, usd; 2, thb; 3, ils);
+        sum.retain(|unit, _| *unit == usd);
+        assert_eq!(sum, sum!(1, usd));
+    }
+    #[test]
+    fn normalize() {
+        let a = "A";
+        let b = "B";
+        let mut sum = sum!(0, a; 3, b);
+        sum.normalize();
+        assert_eq!(sum, sum!(3, b));
+    }
+    #[test]
+    fn normalized() {
+        let a = "A";
+        let b = "B";
+        assert_eq!(sum!(0, a; 3, b).normalized(), sum!(3, b));
+    }
+    #[test]
+    #[should_panic(expected = "Weights add up to zero.")]
+    fn split_proportional_panic_zero_weights() {
+        let usd = "USD";
+        sum!(100, usd).split_proportional(&[("a", 0), ("b", 0)]);
+    }
+    #[test]
+    fn split_proportional() {
+        let usd = "USD";
+        let thb = "THB";
+        let sum = sum!(100, usd; 10, thb);
+        let actual = sum.split_proportional(&[("a", 1), ("b", 1), ("c", 1)]);
+        let expected = vec![
+            ("a", sum!(34, usd; 4, thb)),
+            ("b", sum!(33, usd; 3, thb)),
+            ("c", sum!(33, usd; 3, thb)),
+        ];
+        assert_eq!(actual, expected);
+        let actual = sum.split_proportional(&[("a", 1), ("b", 2)]);
+        let expected =
+            vec![("a", sum!(33, usd; 3, thb)), ("b", sum!(67, usd; 7, thb))];
+        assert_eq!(actual, expected);
+    }
+    #[test]
+    fn split_proportional_with() {
+        let usd = "USD";
+        let sum = sum!(103, usd);
+        let weights = [("a", 1), ("b", 3), ("c", 0), ("d", 1)];
+        let split = |remainder_policy| {
+            sum.split_proportional_with(&weights, remainder_policy)
+                .into_iter()
+                .map(|(key, part)| (key, part.0[&usd]))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            split(RemainderPolicy::LargestWeight),
+            vec![("a", 20), ("b", 63), ("c", 0), ("d", 20)],
+        );
+        assert_eq!(
+            split(RemainderPolicy::FirstEntry),
+            vec![("a", 22), ("b", 61), ("c", 0), ("d", 20)],
+        );
+        assert_eq!(
+            split(RemainderPolicy::Spread),
+            vec![("a", 21), ("b", 62), ("c", 0), ("d", 20)],
+        );
+        let split = sum!(8, usd).split_proportional_with(
+            &[("a", 1), ("b", 1), ("c", 1)],
+            RemainderPolicy::Spread,
+        );
+        assert_eq!(
+            split,
+            vec![
+                ("a", sum!(3, usd)),
+                ("b", sum!(3, usd)),
+                ("c", sum!(2, usd)),
+            ],
+        );
+        let mut sum = Sum::<&str, i64>::default();
+        sum.set_amount_for_unit(-100, usd);
+        let split = sum
+            .split_proportional_with(
+                &[("a", 1), ("b", 1), ("c", 1)],
+                RemainderPolicy::Spread,
+            )
+            .into_iter()
+            .map(|(key, part)| (key, part.0[&usd]))
+            .collect::<Vec<_>>();
+        assert_eq!(split, vec![("a", -34), ("b", -33), ("c", -33)]);
+    }
+    #[test]
+    #[should_panic(expected = "Split amount overflowed.")]
+    fn split_proportional_overflow() {
+        let usd = "USD";
+        sum!(u64::MAX / 2, usd).split_proportional(&[("a", 3), ("b", 1)]);
+    }
+    #[test]
+    fn parse() {
+        let usd = "USD";
+        let eur = "EUR";
+        let resolve_unit = |unit: &str| match unit {
+            "USD" => Some("USD"),
+            "EUR" => Some("EUR"),
+            _ => None,
+        };
+        let actual =
+            Sum::<&str, u64>::parse("100 USD, 50 EUR,20 USD", resolve_unit);
+        assert_eq!(actual, Ok(sum!(120, usd; 50, eur)));
+        let actual = Sum::<&str, u64>::parse(" ", resolve_unit);
+        assert_eq!(actual, Ok(sum!()));
+    }
+    #[test]
+    fn parse_error() {
+        let resolve_unit = |unit: &str| Some(unit.to_owned());
+        let parse = |text| Sum::<String, u64>::parse(text, resolve_unit);
+        match parse("100 USD, x EUR") {
+            Err(ParseSumError::InvalidAmount { index, token }) => {
+                assert_eq!(index, 1);
+                assert_eq!(token, "x EUR");
+            }
+            actual => panic!("unexpected {:?}", actual),
+        }
+        assert_eq!(
+            parse("100 USD,, 5 EUR"),
+            Err(ParseSumError::InvalidToken {
+                index: 1,
+                token: "".to_owned(),
+            }),
+        );
+        assert_eq!(
+            parse("100 USD EUR"),
+            Err(ParseSumError::InvalidToken {
+                index: 0,
+                token: "100 USD EUR".to_owned(),
+            }),
+        );
+        let actual = Sum::<&str, u64>::parse("1 ILS", |_| None);
+        assert_eq!(
+            actual,
+            Err(ParseSumError::UnknownUnit {
+                index: 0,
+                token: "1 ILS".to_owned(),
+            }),
+        );
+        let actual = Sum::<String, u8>::parse("200 X, 100 X", resolve_unit);
+        assert_eq!(
+            actual,
+            Err(ParseSumError::AmountOverflow {
+                index: 1,
+                token: "100 X".to_owned(),
+            }),
+        );
+    }
+    #[test]
+    fn parse_error_fmt_display() {
+        let error = ParseSumError::InvalidAmount {
+            index: 1,
+            token: "x EUR".to_owned(),
+        };
+        assert_eq!(error.to_string(), "invalid amount in token 1 \"x EUR\"");
+    }
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let usd = "USD";
+        let thb = "THB";
+        let sum = sum!(1, usd; 2, thb);
+        let json = serde_json::to_string(&sum).unwrap();
+        assert_eq!(json, r#"{"THB":2,"USD":1}"#);
+        let actual: Sum<&str, u64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(actual, sum);
+    }
 }
@@ -1,4 +1,10 @@
 #[cfg(test)]
+use crate::{
+    book::{AccountKey, TransactionIndex},
+    sum::Sum,
+    transaction::MoveIndex,
+};
+#[cfg(test)]
 pub(crate) type TestBook = crate::book::Book<
     &'static str,
     u64,
@@ -17,3 +23,41 @@ macro_rules! sum {
 }
 #[cfg(test)]
 pub(crate) type TestBalance = crate::Balance<&'static str, i128>;
+/// A move of `(transaction index, debit account key, credit account key,
+/// sum, extra)`, for [append_moves].
+#[cfg(test)]
+pub(crate) type AppendedMove = (
+    usize,
+    AccountKey,
+    AccountKey,
+    Sum<&'static str, u64>,
+    &'static str,
+);
+/// Inserts moves, each after the last move of its transaction.
+/// Transactions are inserted as needed.
+#[cfg(test)]
+pub(crate) fn append_moves(book: &mut TestBook, moves: &[AppendedMove]) {
+    moves.iter().for_each(
+        |(transaction_index, debit_key, credit_key, sum, extra)| {
+            (book.transaction_count()..=*transaction_index).for_each(
+                |transaction_index| {
+                    book.insert_transaction(
+                        TransactionIndex(transaction_index),
+                        "",
+                    );
+                },
+            );
+            let (_, transaction) =
+                book.transactions().nth(*transaction_index).unwrap();
+            let move_index = MoveIndex(transaction.moves().count());
+            book.insert_move(
+                TransactionIndex(*transaction_index),
+                move_index,
+                *debit_key,
+                *credit_key,
+                sum.clone(),
+                extra,
+            );
+        },
+    );
+}
@@ -1,5 +1,8 @@
 use crate::move_::Move;
+use alloc::vec::Vec;
 /// Represents a transaction.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transaction<Unit, SumNumber, Extra, MoveExtra>
 where
     Unit: Ord,
@@ -8,6 +11,11 @@ where
     pub(crate) moves: Vec<Move<Unit, SumNumber, MoveExtra>>,
 }
 /// Used to index moves in a transaction.
+///
+/// Like a [TransactionIndex](crate::TransactionIndex), a move index is a
+/// position. Inserting or removing a move shifts the indexes of the moves
+/// after it in the same transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct MoveIndex(pub usize);
 impl<Unit, SumNumber, Extra, MoveExtra>
     Transaction<Unit, SumNumber, Extra, MoveExtra>
@@ -0,0 +1,32 @@
+use crate::{
+    book::{AccountKey, TransactionIndex},
+    move_::Side,
+    transaction::MoveIndex,
+};
+/// Represents a problem with the integrity of a book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// A move refers to an account that is not in the book.
+    MissingAccount {
+        #[allow(missing_docs)]
+        transaction_index: TransactionIndex,
+        #[allow(missing_docs)]
+        move_index: MoveIndex,
+        #[allow(missing_docs)]
+        side: Side,
+        #[allow(missing_docs)]
+        account_key: AccountKey,
+    },
+    /// A move has the same debit and credit account.
+    SameAccounts {
+        #[allow(missing_docs)]
+        transaction_index: TransactionIndex,
+        #[allow(missing_docs)]
+        move_index: MoveIndex,
+    },
+    /// Tags are kept for an account that is not in the book.
+    TaggedMissingAccount {
+        #[allow(missing_docs)]
+        account_key: AccountKey,
+    },
+}
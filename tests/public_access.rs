@@ -4,39 +4,190 @@ use bookkeeping::*;
 fn balance() {
     type TestBalance = Balance<(), ()>;
     TestBalance::amounts;
+    let _ = |moves: Vec<&Move<(), u8, ()>>| {
+        Balance::<(), i16>::from_moves(moves, AccountKey::default())
+    };
+    Balance::<(), i8>::split_positive_negative;
+    Balance::<(), i8>::abs;
+    let _ = |balance: &Balance<(), i16>| {
+        balance.round(0, |_| 0, RoundingMode::HalfUp)
+    };
     TestBalance::unit_amount;
+    Balance::<(), i8>::format_aligned;
+    Balance::<(), i16>::value_in;
+    let _ = |balance: &Balance<(), i8>| {
+        balance.display_with(|_| ("", 0)).to_string()
+    };
+    let _ = |balance: &Balance<(), i8>| {
+        balance.format_localized(|_| ("", 0), &Locale::EN_US)
+    };
+}
+#[test]
+fn locale() {
+    Locale::EN_US;
+    Locale::DE_DE;
+    let _ = |locale: Locale| (locale.decimal_point, locale.thousands_separator);
 }
 #[test]
 fn book() {
     type TestBook = bookkeeping::Book<(), u8, (), (), ()>;
     TestBook::default;
     TestBook::insert_account;
+    TestBook::insert_account_default;
+    let _ = |book: &mut TestBook| book.try_insert_account((), |_| "");
     TestBook::insert_transaction;
+    TestBook::insert_transaction_default;
     TestBook::insert_move;
+    TestBook::insert_move_signed::<i16>;
+    TestBook::insert_moves;
+    TestBook::insert_opening_balance::<i16>;
+    TestBook::close_account::<i16>;
+    TestBook::reverse_move;
+    TestBook::merge;
+    TestBook::contains_account;
     TestBook::get_account;
+    TestBook::add_account_tag;
+    TestBook::remove_account_tag;
+    TestBook::account_has_tag;
+    TestBook::accounts_with_tag;
+    TestBook::account_has_moves;
+    let _ = |book: &TestBook| {
+        book.account_ancestry(AccountKey::default(), |_, _| None)
+    };
+    let _ = |book: &TestBook| {
+        book.account_depth(AccountKey::default(), |_, _| None)
+    };
+    let _ = |book: &Book<(), u8, &str, (), ()>| {
+        book.account_path(AccountKey::default(), |_, _| None, |name| name, ":")
+    };
+    TestBook::account_count;
+    TestBook::transaction_count;
+    TestBook::move_count;
+    TestBook::unit_count;
+    TestBook::units;
+    TestBook::contains_unit;
     TestBook::accounts;
+    let _ = |book: &TestBook| book.find_account(|_| true).is_some();
     TestBook::transactions;
     TestBook::set_account;
     TestBook::set_transaction_extra;
     TestBook::set_move_extra;
     TestBook::account_balance_at_transaction::<i16>;
+    TestBook::account_balance::<i16>;
+    TestBook::account_unit_balance::<i16>;
+    TestBook::account_balances::<i16>;
+    TestBook::combined_balance::<i16>;
+    TestBook::account_statement::<i16>;
     TestBook::remove_move;
+    TestBook::dedup_moves;
+    let _ = |book: &mut TestBook| {
+        book.dedup_moves_by_key(|transaction_index, _| transaction_index)
+    };
     TestBook::set_move_sum;
+    TestBook::rename_unit;
     TestBook::set_move_side;
+    TestBook::remove_account;
+    TestBook::validate;
+    TestBook::clear;
+    TestBook::checkpoint;
+    TestBook::restore;
+    TestBook::unit_moves;
+    TestBook::moves_between;
+    let _ = |book: &Book<&str, u8, &str, (), ()>, w: &mut String| {
+        book.balance_sheet::<i16>(w, |_, _| Some(AccountClass::Asset))
+    };
+    TestBook::flow_between::<u8>;
+    TestBook::flow_graph::<u8>;
+    TestBook::query;
+    let _ = |book: &mut TestBook| book.on_change(|_| {});
+    let _ = |book: &TestBook| {
+        book.income_statement::<i16>(
+            TransactionIndex(0),
+            TransactionIndex(0),
+            |_, _| false,
+            |_, _| false,
+        )
+    };
+}
+#[test]
+fn exchange_rates() {
+    type TestExchangeRates = ExchangeRates<(), u8>;
+    let _ = |ratio: Ratio<u8>| (ratio.numerator, ratio.denominator);
+    TestExchangeRates::default;
+    TestExchangeRates::set_rate;
+    TestExchangeRates::rate;
+}
+#[test]
+fn income_statement() {
+    type TestIncomeStatement = IncomeStatement<(), ()>;
+    TestIncomeStatement::income;
+    TestIncomeStatement::expenses;
+    TestIncomeStatement::net;
+}
+#[test]
+fn merge_report() {
+    MergeReport::account_key;
+    MergeReport::account_keys;
+    MergeReport::first_transaction_index;
+}
+#[test]
+fn remove_report() {
+    type TestRemoveReport = RemoveReport<()>;
+    TestRemoveReport::extra;
+    TestRemoveReport::into_extra;
+    TestRemoveReport::reassigned_moves;
+    TestRemoveReport::removed_moves;
 }
 #[test]
 fn move_() {
     type TestMove = Move<(), (), ()>;
     TestMove::side_key;
     TestMove::sum;
+    Move::<(), u8, ()>::amount;
     TestMove::extra;
+    Move::<(), u8, ()>::structural_eq;
+}
+#[test]
+fn move_builder() {
+    type TestMoveBuilder = MoveBuilder<(), (), ()>;
+    TestMoveBuilder::default;
+    TestMoveBuilder::debit;
+    TestMoveBuilder::credit;
+    TestMoveBuilder::sum;
+    TestMoveBuilder::extra;
+    TestMoveBuilder::build;
+}
+#[test]
+fn query() {
+    type TestQuery<'a> = Query<'a, (), u8, (), (), ()>;
+    TestQuery::account;
+    TestQuery::unit;
+    TestQuery::between;
+    let _ = |query: TestQuery<'static>| query.filter(|_| true);
+    TestQuery::moves;
+    TestQuery::balance::<i16>;
 }
 #[test]
 fn sum() {
     type TestSum = Sum<(), u64>;
     TestSum::default;
+    TestSum::of;
+    TestSum::unit;
+    TestSum::set_unit;
+    TestSum::checked_sub;
     TestSum::set_amount_for_unit;
     TestSum::amounts;
+    Sum::<(), u8>::format_aligned;
+    TestSum::major_minor;
+    TestSum::convert;
+    TestSum::total_in;
+    TestSum::semantic_eq;
+    let _ = |sum: &mut TestSum| sum.retain(|_, _| true);
+    TestSum::normalize;
+    TestSum::normalized;
+    TestSum::split_proportional::<()>;
+    TestSum::split_proportional_with::<()>;
+    let _ = || TestSum::parse("", |_| None);
 }
 #[test]
 fn transaction() {